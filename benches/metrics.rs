@@ -0,0 +1,79 @@
+// Times the integer metrics on their own and inside compute(), run with
+// cargo bench --bench metrics
+use discrete_voronoi::metric::{Chamfer, Chebyshev, Euclidean, Manhattan, Metric};
+use discrete_voronoi::{BoundingBox, GridIdx, Site, VoronoiBuilder};
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 512;
+const ROUNDS: usize = 15;
+
+// Fastest of a few runs, to keep the noise of other processes out
+fn fastest<F: FnMut()>(mut run: F) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let started = Instant::now();
+            run();
+            started.elapsed()
+        })
+        .min()
+        .expect("at least one round")
+}
+
+fn distances<M, S>(name: &str, sites: &[S], cells: &[GridIdx])
+where
+    M: Metric<Output = f64>,
+    S: Site
+{
+    let time = fastest(|| {
+        let mut total = 0.0;
+        for site in sites {
+            for cell in cells {
+                total += M::distance(black_box(site), cell);
+            }
+        }
+        black_box(total);
+    });
+    let per_distance = time.as_nanos() as f64 / (sites.len() * cells.len()) as f64;
+    println!("{:<28} {:>10.2?} {:>6.2} ns/distance", name, time, per_distance);
+}
+
+fn compute<M, S>(name: &str, sites: &[S])
+where
+    M: Metric,
+    S: Site + Clone
+{
+    let bounds = BoundingBox::new(0, 0, SIZE, SIZE);
+    let time = fastest(|| {
+        let mut tesselation = VoronoiBuilder::new(sites.to_vec())
+            .metric::<M>()
+            .bounds(bounds)
+            .build()
+            .unwrap();
+        tesselation.compute();
+        black_box(tesselation.steps());
+    });
+    println!("{:<28} {:>10.2?}", name, time);
+}
+
+fn main() {
+    let cells: Vec<GridIdx> = BoundingBox::new(0, 0, SIZE, SIZE).coordinates_iter().collect();
+    let integer: Vec<(isize, isize, f32)> = (0..64)
+        .map(|i| ((i * 193 % SIZE) as isize, (i * 71 % SIZE) as isize, 1f32))
+        .collect();
+    let float: Vec<(f64, f64, f32)> = integer.iter().map(|&(x, y, weight)| (x as f64, y as f64, weight)).collect();
+
+    distances::<Manhattan, _>("manhattan, integer sites", &integer[..8], &cells);
+    distances::<Manhattan, _>("manhattan, float sites", &float[..8], &cells);
+    distances::<Chebyshev, _>("chebyshev, integer sites", &integer[..8], &cells);
+    distances::<Chebyshev, _>("chebyshev, float sites", &float[..8], &cells);
+    distances::<Chamfer, _>("chamfer, integer sites", &integer[..8], &cells);
+    distances::<Chamfer, _>("chamfer, float sites", &float[..8], &cells);
+    distances::<Euclidean, _>("euclidean, integer sites", &integer[..8], &cells);
+
+    compute::<Manhattan, _>("compute manhattan", &integer);
+    compute::<Chebyshev, _>("compute chebyshev", &integer);
+    compute::<Chamfer, _>("compute chamfer", &integer);
+    compute::<Euclidean, _>("compute euclidean", &integer);
+}
@@ -0,0 +1,99 @@
+use crate::discrete_voronoi::VoronoiTesselation;
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+use arrow_array::{ArrayRef, Float32Array, Int64Array, RecordBatch, UInt32Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+#[cfg(feature = "parquet")]
+use parquet::arrow::ArrowWriter;
+#[cfg(feature = "parquet")]
+use parquet::errors::ParquetError;
+
+#[cfg(feature = "parquet")]
+use std::io::Write;
+use std::sync::Arc;
+
+// Schema of the per-cell assignment table, unowned cells have null owner,
+// distance and claim step
+pub fn cell_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("x", DataType::Int64, false),
+        Field::new("y", DataType::Int64, false),
+        Field::new("owner", DataType::UInt32, true),
+        Field::new("distance", DataType::Float32, true),
+        Field::new("claim_step", DataType::UInt32, true),
+    ])
+}
+
+// One row per cell in row-major order
+pub fn to_record_batch<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Result<RecordBatch, ArrowError>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let cells = tesselation.cells();
+
+    let mut xs = Vec::with_capacity(cells.len());
+    let mut ys = Vec::with_capacity(cells.len());
+    let mut owners = Vec::with_capacity(cells.len());
+    let mut distances = Vec::with_capacity(cells.len());
+    let mut claim_steps = Vec::with_capacity(cells.len());
+
+    for (position, (idx, cell)) in cells.iter().enumerate() {
+        let (x, y) = idx.coordinates();
+        xs.push(x as i64);
+        ys.push(y as i64);
+        owners.push(cell.owner().map(|owner| owner.0));
+        distances.push(cell.owner().map(|owner| {
+            let site = tesselation.site(owner).unwrap();
+            M::distance(site, &idx).into() as f32
+        }));
+        claim_steps.push(cells.claim_step(position));
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(xs)),
+        Arc::new(Int64Array::from(ys)),
+        Arc::new(UInt32Array::from(owners)),
+        Arc::new(Float32Array::from(distances)),
+        Arc::new(UInt32Array::from(claim_steps)),
+    ];
+
+    RecordBatch::try_new(Arc::new(cell_schema()), columns)
+}
+
+#[cfg(feature = "parquet")]
+pub fn write_parquet<S, M, W>(tesselation: &VoronoiTesselation<S, M>, writer: W) -> Result<(), ParquetError>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>,
+    W: Write + Send
+{
+    let batch = to_record_batch(tesselation)?;
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+
+    #[test]
+    fn record_batch_has_row_per_cell() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (3, 2, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        let batch = to_record_batch(&tess).unwrap();
+
+        assert_eq!(batch.num_rows(), tess.cells().len());
+        assert_eq!(batch.num_columns(), 5);
+    }
+}
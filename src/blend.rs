@@ -0,0 +1,91 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::metric::Metric;
+use crate::site::Site;
+
+// Weights of the closest owners of a cell, sorted by descending weight and
+// summing to 1
+pub type BlendWeights = Vec<(SiteOwner, f64)>;
+
+// Blends every cell between its k closest sites under the metric, returned
+// in row-major order. Weights are the softmax of the negative distances
+// divided by the temperature, so low temperatures approach the hard regions
+// and high ones approach an even mix. A temperature that is not positive
+// gives the closest site all of the weight, ties going to the lower id.
+// Every site is measured against every cell, the tesselation does not have
+// to be computed.
+pub fn blend_weights<S, M>(tesselation: &VoronoiTesselation<S, M>, k: usize, temperature: f64) -> Vec<BlendWeights>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let sites: Vec<(SiteOwner, &S)> = tesselation
+        .owners()
+        .filter_map(|owner| tesselation.site(owner).map(|site| (owner, site)))
+        .collect();
+    let k = if temperature > 0.0 { k.min(sites.len()) } else { k.min(1) };
+
+    tesselation
+        .cells()
+        .iter()
+        .map(|(idx, _)| {
+            let mut nearest: Vec<(SiteOwner, f64)> = sites
+                .iter()
+                .map(|&(owner, site)| (owner, M::distance(site, &idx).into()))
+                .collect();
+            nearest.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            nearest.truncate(k);
+
+            // Shifted by the closest distance so the exponentials can not
+            // all underflow
+            let closest = nearest.first().map_or(0.0, |&(_, distance)| distance);
+            for (_, weight) in &mut nearest {
+                *weight = if k == 1 { 1.0 } else { (-(*weight - closest) / temperature).exp() };
+            }
+            let total: f64 = nearest.iter().map(|&(_, weight)| weight).sum();
+            for (_, weight) in &mut nearest {
+                *weight /= total;
+            }
+
+            nearest
+        })
+        .collect()
+}
+
+// Interpolates a value per site, indexed by owner id, across the cells with
+// the blend weights, returned in row-major order
+pub fn interpolate(weights: &[BlendWeights], values: &[f64]) -> Vec<f64> {
+    weights
+        .iter()
+        .map(|cell| cell.iter().map(|&(owner, weight)| weight * values[owner.0 as usize]).sum())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn blend_between_nearest_sites() {
+        let sites = vec![(0, 0, 1f32), (4, 0, 1f32), (9, 9, 1f32)];
+        let tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 10, 10))
+            .build()
+            .unwrap();
+
+        let weights = blend_weights(&tess, 2, 1.0);
+        assert_eq!(weights[2], vec![(SiteOwner(0), 0.5), (SiteOwner(1), 0.5)]);
+        assert_eq!(weights[0][0].0, SiteOwner(0));
+        assert!((weights[0][0].1 - 1.0 / (1.0 + (-4f64).exp())).abs() < 1e-12);
+        assert_eq!(interpolate(&weights, &[0.0, 10.0, 100.0])[2], 5.0);
+
+        // Hard regions without a temperature
+        assert_eq!(blend_weights(&tess, 3, 0.0)[1], vec![(SiteOwner(0), 1.0)]);
+        assert_eq!(blend_weights(&tess, 1, 5.0)[3], vec![(SiteOwner(1), 1.0)]);
+        assert_eq!(blend_weights(&tess, 9, 1.0)[0].len(), 3);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::discrete_voronoi::{DuplicatePolicy, VoronoiBuilder, VoronoiTesselation};
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+// Outcome of alternating between assigning cells and re-estimating sites
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clustering<S> {
+    // Sites after the last round, in the order they were given
+    pub sites: Vec<S>,
+    // Site of every cell in row-major order from the last assignment
+    pub labels: Vec<Option<usize>>,
+    pub iterations: usize,
+    // Whether a round left every site unchanged before running out of rounds
+    pub converged: bool
+}
+
+// Assigns every cell to its closest site under the metric and replaces each
+// site with the estimate from the cells of its region, until no site changes
+// or the rounds run out. Estimating sites as the centroid of their cells is
+// Lloyd relaxation, other objectives such as a weighted medoid turn this into
+// a clustering of the grid. Sites whose region is empty stay where they are.
+pub fn refine<S, M, F>(
+    sites: Vec<S>,
+    bounds: BoundingBox,
+    max_iterations: usize,
+    mut estimate: F
+) -> Result<Clustering<S>, VoronoiError>
+where
+    S: Site + Clone + PartialEq,
+    M: Metric,
+    F: FnMut(&S, &[GridIdx]) -> S
+{
+    let mut sites = sites;
+    let mut iterations = 0;
+
+    loop {
+        let labels = assign::<S, M>(&sites, bounds)?;
+        if iterations >= max_iterations {
+            return Ok(Clustering {
+                sites,
+                labels,
+                iterations,
+                converged: false
+            });
+        }
+
+        let mut regions: Vec<Vec<GridIdx>> = vec![Vec::new(); sites.len()];
+        for (idx, label) in bounds.coordinates_iter().zip(&labels) {
+            if let Some(label) = *label {
+                regions[label].push(idx);
+            }
+        }
+
+        let estimated: Vec<S> = sites
+            .iter()
+            .zip(&regions)
+            .map(|(site, cells)| if cells.is_empty() { site.clone() } else { estimate(site, cells) })
+            .collect();
+        iterations += 1;
+
+        if estimated == sites {
+            return Ok(Clustering {
+                sites,
+                labels,
+                iterations,
+                converged: true
+            });
+        }
+        sites = estimated;
+    }
+}
+
+// Mean position of the cells, None if there are none
+pub fn centroid(cells: &[GridIdx]) -> Option<(f64, f64)> {
+    if cells.is_empty() {
+        return None;
+    }
+
+    let (x, y) = cells.iter().fold((0.0, 0.0), |(x, y), idx| {
+        let (cell_x, cell_y) = idx.coordinates();
+        (x + cell_x as f64, y + cell_y as f64)
+    });
+    Some((x / cells.len() as f64, y / cells.len() as f64))
+}
+
+// Centroid of every region indexed by owner id with the cells weighted by the
+// mass raster, given in row-major order over the bounds, in a single pass over
+// the cells. Negative masses count as zero and regions without any mass have
+// no centroid.
+pub fn weighted_centroids<S, M>(
+    tesselation: &VoronoiTesselation<S, M>,
+    mass: &[f64]
+) -> Result<Vec<Option<(f64, f64)>>, VoronoiError>
+where
+    S: Site,
+    M: Metric
+{
+    let cells = tesselation.cells();
+    if mass.len() != cells.len() {
+        return Err(VoronoiError::BufferLength {
+            expected: cells.len(),
+            actual: mass.len()
+        });
+    }
+
+    let mut sums = vec![(0.0, 0.0, 0.0); tesselation.len()];
+    for ((idx, cell), &value) in cells.iter().zip(mass) {
+        let owner = match cell.owner() {
+            Some(owner) if value > 0.0 => owner,
+            _ => continue
+        };
+        let (x, y) = idx.coordinates();
+        let sum = &mut sums[owner.0 as usize];
+        sum.0 += x as f64 * value;
+        sum.1 += y as f64 * value;
+        sum.2 += value;
+    }
+
+    Ok(sums
+        .into_iter()
+        .map(|(x, y, total)| (total > 0.0).then(|| (x / total, y / total)))
+        .collect())
+}
+
+// The cell with the smallest weighted sum of distances under the metric to
+// all other cells, ties going to the first one. Always lies inside of the
+// region, unlike the centroid. Compares every pair of cells.
+pub fn weighted_medoid<M, W>(cells: &[GridIdx], weight: W) -> Option<GridIdx>
+where
+    M: Metric,
+    M::Output: Into<f64>,
+    W: Fn(GridIdx) -> f64
+{
+    let weights: Vec<f64> = cells.iter().map(|&idx| weight(idx)).collect();
+    let mut best: Option<(GridIdx, f64)> = None;
+
+    for &candidate in cells {
+        let center = candidate.coordinates();
+        let cost: f64 = cells
+            .iter()
+            .zip(&weights)
+            .map(|(idx, &weight)| weight * M::distance(&center, idx).into())
+            .sum();
+
+        if best.is_none_or(|(_, best_cost)| cost < best_cost) {
+            best = Some((candidate, cost));
+        }
+    }
+
+    best.map(|(idx, _)| idx)
+}
+
+fn assign<S, M>(sites: &[S], bounds: BoundingBox) -> Result<Vec<Option<usize>>, VoronoiError>
+where
+    S: Site + Clone,
+    M: Metric
+{
+    let mut tesselation = VoronoiBuilder::new(sites.iter().cloned())
+        .metric::<M>()
+        .bounds(bounds)
+        .duplicates(DuplicatePolicy::AllowDuplicates)
+        .build()?;
+    tesselation.compute();
+
+    Ok(tesselation
+        .cells()
+        .iter()
+        .map(|(_, cell)| cell.owner().and_then(|owner| tesselation.original_index(owner)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn cluster_around_heavy_cells() {
+        // Two heavy spots in an otherwise light grid
+        let bounds = BoundingBox::new(0, 0, 12, 6);
+        let weight = |idx: GridIdx| match idx.coordinates() {
+            (2, 2) | (9, 3) => 100.0,
+            _ => 1.0
+        };
+        let sites = vec![(0isize, 0isize), (11, 5)];
+
+        let clustering = refine::<_, Manhattan, _>(sites.clone(), bounds, 20, |_, cells| {
+            weighted_medoid::<Manhattan, _>(cells, weight).unwrap().coordinates()
+        })
+        .unwrap();
+        assert!(clustering.converged);
+        assert_eq!(clustering.sites, vec![(2, 2), (9, 3)]);
+        assert_eq!(clustering.labels[0], Some(0));
+        assert_eq!(clustering.labels[71], Some(1));
+
+        // Stops after the given number of rounds
+        let clustering = refine::<_, Manhattan, _>(sites, bounds, 0, |site, _| *site).unwrap();
+        assert!(!clustering.converged);
+        assert_eq!(clustering.iterations, 0);
+
+        assert_eq!(centroid(&[GridIdx::from((0, 0)), GridIdx::from((2, 4))]), Some((1.0, 2.0)));
+        assert_eq!(centroid(&[]), None);
+    }
+
+    #[test]
+    fn centroids_follow_mass() {
+        let sites = vec![(0, 0, 1f32), (5, 0, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 6, 1);
+        let mut tess = VoronoiBuilder::new(sites).bounds(bounds).build().unwrap();
+        tess.compute();
+
+        let mass = [1.0, 0.0, 3.0, 0.0, 0.0, 0.0];
+        let centroids = weighted_centroids(&tess, &mass).unwrap();
+        assert_eq!(centroids, vec![Some((1.5, 0.0)), None]);
+        assert_eq!(weighted_centroids(&tess, &mass[1..]), Err(VoronoiError::BufferLength { expected: 6, actual: 5 }));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::grid::GridIdx;
+use crate::metric::Metric;
+use crate::site::Site;
+
+use std::collections::HashMap;
+
+// Owner transition of a cell between two tesselations, (old, new)
+pub type OwnerChange = (Option<SiteOwner>, Option<SiteOwner>);
+
+// Cells that changed owner between two tesselations over the same bounds,
+// grouped by the (old, new) owner pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TesselationDiff {
+    changes: HashMap<OwnerChange, Vec<GridIdx>>
+}
+
+impl TesselationDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // Total number of cells that changed owner
+    pub fn changed_cells(&self) -> usize {
+        self.changes.values().map(|cells| cells.len()).sum()
+    }
+
+    pub fn changes(&self) -> &HashMap<OwnerChange, Vec<GridIdx>> {
+        &self.changes
+    }
+
+    // Cells that moved from one owner to another, in row-major order
+    pub fn cells_changed(&self, old: Option<SiteOwner>, new: Option<SiteOwner>) -> &[GridIdx] {
+        self.changes.get(&(old, new)).map_or(&[], |cells| cells.as_slice())
+    }
+}
+
+// Compares the ownership of every cell, owners are matched by id so this is
+// only meaningful for tesselations built from the same sites. Returns None if
+// the bounds of the two tesselations differ.
+pub fn diff<S, M, T, N>(old: &VoronoiTesselation<S, M>, new: &VoronoiTesselation<T, N>) -> Option<TesselationDiff>
+where
+    S: Site,
+    M: Metric,
+    T: Site,
+    N: Metric
+{
+    if old.bounds() != new.bounds() {
+        return None;
+    }
+
+    let mut changes: HashMap<OwnerChange, Vec<GridIdx>> = HashMap::new();
+    for ((idx, old_cell), (_, new_cell)) in old.cells().iter().zip(new.cells()) {
+        if old_cell.owner() != new_cell.owner() {
+            changes
+                .entry((old_cell.owner(), new_cell.owner()))
+                .or_default()
+                .push(idx);
+        }
+    }
+
+    Some(TesselationDiff { changes })
+}
+
+// Agreement statistics between two tesselations over the same bounds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    total_cells: usize,
+    confusion: HashMap<OwnerChange, usize>,
+    ious: HashMap<SiteOwner, f64>
+}
+
+impl Comparison {
+    // Fraction of cells, between 0 and 1, that have the same owner in both
+    pub fn agreement(&self) -> f64 {
+        if self.total_cells == 0 {
+            return 1.0;
+        }
+
+        let agreeing: usize = self.confusion
+            .iter()
+            .filter(|&(&(first, second), _)| first == second)
+            .map(|(_, &count)| count)
+            .sum();
+
+        agreeing as f64 / self.total_cells as f64
+    }
+
+    // Number of cells for every (first, second) owner pair, including cells
+    // that agree
+    pub fn confusion_matrix(&self) -> &HashMap<OwnerChange, usize> {
+        &self.confusion
+    }
+
+    // Intersection over union of the region of the owner in both tesselations
+    pub fn iou(&self, owner: SiteOwner) -> Option<f64> {
+        self.ious.get(&owner).cloned()
+    }
+
+    pub fn ious(&self) -> &HashMap<SiteOwner, f64> {
+        &self.ious
+    }
+}
+
+// Computes per-site IoU, agreement and the confusion matrix. Like diff,
+// owners are matched by id and None is returned if the bounds differ.
+pub fn compare<S, M, T, N>(first: &VoronoiTesselation<S, M>, second: &VoronoiTesselation<T, N>) -> Option<Comparison>
+where
+    S: Site,
+    M: Metric,
+    T: Site,
+    N: Metric
+{
+    if first.bounds() != second.bounds() {
+        return None;
+    }
+
+    let mut confusion: HashMap<OwnerChange, usize> = HashMap::new();
+    for ((_, first_cell), (_, second_cell)) in first.cells().iter().zip(second.cells()) {
+        *confusion.entry((first_cell.owner(), second_cell.owner())).or_insert(0) += 1;
+    }
+
+    let mut first_areas: HashMap<SiteOwner, usize> = HashMap::new();
+    let mut second_areas: HashMap<SiteOwner, usize> = HashMap::new();
+    for (&(first_owner, second_owner), &count) in &confusion {
+        if let Some(owner) = first_owner {
+            *first_areas.entry(owner).or_insert(0) += count;
+        }
+        if let Some(owner) = second_owner {
+            *second_areas.entry(owner).or_insert(0) += count;
+        }
+    }
+
+    let owners = first.owned_sites()
+        .into_iter()
+        .map(|(owner, _)| owner)
+        .chain(second.owned_sites().into_iter().map(|(owner, _)| owner));
+    let mut ious = HashMap::new();
+    for owner in owners {
+        let intersection = confusion.get(&(Some(owner), Some(owner))).cloned().unwrap_or(0);
+        let first_area = first_areas.get(&owner).cloned().unwrap_or(0);
+        let second_area = second_areas.get(&owner).cloned().unwrap_or(0);
+        let union = first_area + second_area - intersection;
+
+        let iou = if union == 0 { 0.0 } else { intersection as f64 / union as f64 };
+        ious.insert(owner, iou);
+    }
+
+    Some(Comparison {
+        total_cells: first.cells().len(),
+        confusion,
+        ious
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+
+    #[test]
+    fn diff_reports_moved_cells() {
+        let bounds = BoundingBox::new(0, 0, 8, 1);
+
+        let mut before = VoronoiBuilder::new(vec![(0, 0, 1f32), (7, 0, 1f32)])
+            .bounds(bounds)
+            .build().unwrap();
+        before.compute();
+        let mut after = VoronoiBuilder::new(vec![(0, 0, 1f32), (7, 0, 1f32)])
+            .bounds(bounds)
+            .build().unwrap();
+        after.compute();
+
+        assert!(diff(&before, &after).unwrap().is_empty());
+
+        let mut shifted = VoronoiBuilder::new(vec![(0, 0, 1f32), (7, 0, 5f32)])
+            .metric::<crate::metric::AdditiveWeightedEuclidean>()
+            .bounds(bounds)
+            .build().unwrap();
+        shifted.compute();
+
+        let changes = diff(&before, &shifted).unwrap();
+        assert!(changes.changed_cells() > 0);
+        assert!(!changes.cells_changed(Some(SiteOwner(0)), Some(SiteOwner(1))).is_empty());
+    }
+
+    #[test]
+    fn diff_requires_same_bounds() {
+        let small = VoronoiBuilder::new(vec![(0, 0, 1f32)]).bounds(BoundingBox::new(0, 0, 2, 2)).build().unwrap();
+        let large = VoronoiBuilder::new(vec![(0, 0, 1f32)]).bounds(BoundingBox::new(0, 0, 3, 3)).build().unwrap();
+
+        assert!(diff(&small, &large).is_none());
+    }
+
+    #[test]
+    fn compare_identical_and_shifted() {
+        let bounds = BoundingBox::new(0, 0, 8, 8);
+        let sites = vec![(1, 1, 1f32), (6, 6, 3f32)];
+
+        let mut plain = VoronoiBuilder::new(sites.clone()).bounds(bounds).build().unwrap();
+        plain.compute();
+
+        let identical = compare(&plain, &plain).unwrap();
+        assert_eq!(identical.agreement(), 1.0);
+        assert_eq!(identical.iou(SiteOwner(0)), Some(1.0));
+        assert_eq!(identical.iou(SiteOwner(1)), Some(1.0));
+
+        let mut weighted = VoronoiBuilder::new(sites)
+            .metric::<crate::metric::AdditiveWeightedEuclidean>()
+            .bounds(bounds)
+            .build().unwrap();
+        weighted.compute();
+
+        let shifted = compare(&plain, &weighted).unwrap();
+        assert!(shifted.agreement() < 1.0);
+        assert!(shifted.iou(SiteOwner(0)).unwrap() < 1.0);
+        assert_eq!(shifted.confusion_matrix().values().sum::<usize>(), 64);
+    }
+}
@@ -0,0 +1,36 @@
+use std::ops::AddAssign;
+use std::time::Duration;
+
+// Work done by the computation, only gathered when the builder enables
+// instrumentation. Counts add up over every step since the last reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeCounters {
+    pub steps: u32,
+    // Neighbouring cells looked at from the frontier
+    pub cells_scanned: usize,
+    // Claims made on cells by growing regions, after removing repeats
+    pub claims: usize,
+    // Cells that changed owner, not counting the seeds
+    pub cells_claimed: usize,
+    // Cells claimed by more than one site, or by a site while owned by another
+    pub conflicts_resolved: usize,
+    // Cells left unowned by a tie under TieBreak::Unowned
+    pub ties: usize,
+    // Time spent finding the claims of every step
+    pub gather_time: Duration,
+    // Time spent settling the claims of every step
+    pub resolve_time: Duration
+}
+
+impl AddAssign for ComputeCounters {
+    fn add_assign(&mut self, other: ComputeCounters) {
+        self.steps += other.steps;
+        self.cells_scanned += other.cells_scanned;
+        self.claims += other.claims;
+        self.cells_claimed += other.cells_claimed;
+        self.conflicts_resolved += other.conflicts_resolved;
+        self.ties += other.ties;
+        self.gather_time += other.gather_time;
+        self.resolve_time += other.resolve_time;
+    }
+}
@@ -0,0 +1,61 @@
+use crate::discrete_voronoi::SiteOwner;
+use crate::grid::GridIdx;
+
+use std::fmt;
+
+// A reason the region growing may not match the true tesselation for the
+// given sites and metric. VoronoiTesselation::compute_exact() gives the true
+// result at the cost of measuring every cell against every site.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricWarning {
+    // Another site is strictly closer to the site's own cell, so its region
+    // does not contain its seed and growing from there is wrong
+    SiteOutsideRegion(SiteOwner),
+    // The border between the two sites bends enough within the bounds to
+    // split regions into several pieces, and growth only reaches the piece
+    // holding the seed
+    DisconnectedRegions(SiteOwner, SiteOwner)
+}
+
+impl fmt::Display for MetricWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MetricWarning::SiteOutsideRegion(owner) => {
+                write!(f, "site {} is not the closest site to its own cell", owner.0)?
+            },
+            MetricWarning::DisconnectedRegions(first, second) => write!(
+                f,
+                "the weights of sites {} and {} can split regions of this metric into pieces",
+                first.0, second.0
+            )?
+        }
+
+        write!(f, ", use compute_exact() for a correct result")
+    }
+}
+
+// A cell whose owner is not its closest site
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mismatch {
+    pub coordinates: GridIdx,
+    pub owner: Option<SiteOwner>,
+    pub nearest: SiteOwner,
+    // How much further the owner is than the closest site, infinite for cells
+    // that are unowned without a tie
+    pub excess: f64
+}
+
+// Result of checking cells against the closest site under the metric
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    pub checked: usize,
+    pub mismatches: usize,
+    // Mismatches with the largest excess, worst first
+    pub worst: Vec<Mismatch>
+}
+
+impl Verification {
+    pub fn is_exact(&self) -> bool {
+        self.mismatches == 0
+    }
+}
@@ -1,10 +1,143 @@
-use grid::{BoundingBox, Cell, Grid, GridIdx};
-use metric::{Euclidean, Metric};
-use site::Site;
+use crate::counters::ComputeCounters;
+use crate::diagnostics::{MetricWarning, Mismatch, Verification};
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, Cell, Cells, Grid, GridIdx, MAX_SITES};
+use crate::map::VoronoiMap;
+use crate::metric::{Euclidean, Metric};
+use crate::rasterizer::{CellRasterizer, SiteRasterizer};
+use crate::site::{Point, Site, SiteMut, Weight};
+use crate::subcell::sample_cell;
+use crate::summary::Summary;
 
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
 use std::marker::PhantomData;
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::hash::Hash;
+use std::io;
+use std::time::Instant;
+use std::ops::{Index, IndexMut};
+#[cfg(feature = "rayon")]
+use std::sync::Arc;
+
+// How the builder treats multiple sites with the same coordinates
+#[derive(Debug)]
+pub enum DuplicatePolicy<S> {
+    // Keep the site that came first in the input and drop the rest
+    KeepFirst,
+    // Combine co-located sites into one, in input order, typically by adding
+    // their weights
+    MergeWeights(fn(S, S) -> S),
+    // Fail the build with VoronoiError::DuplicateSite
+    Error,
+    // Keep every site, the one closest to the shared cell under the metric
+    // owns it and ties go to the site that came first
+    AllowDuplicates
+}
+
+impl<S> Clone for DuplicatePolicy<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for DuplicatePolicy<S> {}
+
+// How the builder treats sites with a weight the metric cannot use, such as
+// a zero weight for MultWeightedEuclidean
+#[derive(Debug)]
+pub enum WeightPolicy<S> {
+    // Fail the build with VoronoiError::InvalidWeight
+    Error,
+    // Replace the site, typically with one carrying the smallest usable weight.
+    // The build fails if the replacement is still invalid.
+    Clamp(fn(S) -> S),
+    // Drop the site
+    Skip,
+    // Keep the site as it is
+    Allow
+}
+
+impl<S> Clone for WeightPolicy<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for WeightPolicy<S> {}
+
+// How a cell is settled when the closest sites claiming it are at the same
+// distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    // The site that came first in the builder input takes the cell
+    #[default]
+    FirstSite,
+    // The cell is left unowned and stops the growth of all tied sites,
+    // outlining the borders between regions
+    Unowned
+}
+
+// How the builder treats sites without any seed cell inside of the bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutOfBoundsPolicy {
+    // Leave the site out of the tesselation
+    #[default]
+    Drop,
+    // Seed the site from the cell of the bounds closest to it, distances are
+    // still measured from where the site really is
+    Clamp,
+    // Grow the bounds until they hold the seed cells of every site
+    Expand,
+    // Fail the build with VoronoiError::SiteOutOfBounds
+    Error
+}
+
+// A site that was dropped or merged because of a co-located site, as indices
+// into the builder input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateSite {
+    pub index: usize,
+    pub duplicate_of: usize
+}
+
+// Which neighbouring region takes over the cells of a region that is too small
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeTarget {
+    // The neighbour with the most cells, ties going to the lower id
+    #[default]
+    Largest,
+    // The neighbour whose site is closest to the site of the small region
+    Nearest
+}
+
+// A site whose region was merged into a neighbouring region, as indices into
+// the builder input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergedRegion {
+    pub index: usize,
+    pub merged_into: usize
+}
+
+// Input sites that did not make it into the tesselation as given, as indices
+// into the builder input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildReport {
+    // Sites dropped or merged by the duplicate policy
+    pub duplicates: Vec<DuplicateSite>,
+    // Sites outside of the bounds that were dropped or clamped by the out of
+    // bounds policy, in input order
+    pub clipped: Vec<usize>,
+    // Sites skipped or clamped by the weight policy, in input order
+    pub invalid_weights: Vec<usize>
+}
+
+impl BuildReport {
+    // True if every input site became a site of the tesselation
+    pub fn is_clean(&self) -> bool {
+        self.duplicates.is_empty() && self.clipped.is_empty() && self.invalid_weights.is_empty()
+    }
+}
 
 #[derive(Debug)]
 pub struct VoronoiBuilder<S, M>
@@ -14,21 +147,39 @@ where
 {
     sites: Vec<S>,
     metric: PhantomData<M>,
-    bounds: Option<BoundingBox>
+    bounds: Option<BoundingBox>,
+    duplicates: DuplicatePolicy<S>,
+    weights: WeightPolicy<S>,
+    rasterizer: Box<dyn SiteRasterizer<S>>,
+    out_of_bounds: OutOfBoundsPolicy,
+    ties: TieBreak,
+    check_metric: bool,
+    instrument: bool,
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<Arc<rayon::ThreadPool>>
 }
 
 impl<S> VoronoiBuilder<S, Euclidean>
 where
     S: Site
 {
-    // Will remove all sites that have the same coordinates
-    pub fn new(mut sites: Vec<S>) -> Self {
-        sites.sort_unstable_by_key(|site| site.coordinates());
-        sites.dedup_by_key(|site| site.coordinates());
+    pub fn new<I>(sites: I) -> Self
+    where
+        I: IntoIterator<Item = S>
+    {
         VoronoiBuilder {
-            sites,
+            sites: sites.into_iter().collect(),
             metric: PhantomData,
-            bounds: None
+            bounds: None,
+            duplicates: DuplicatePolicy::KeepFirst,
+            weights: WeightPolicy::Error,
+            rasterizer: Box::new(CellRasterizer),
+            out_of_bounds: OutOfBoundsPolicy::default(),
+            ties: TieBreak::default(),
+            check_metric: false,
+            instrument: false,
+            #[cfg(feature = "rayon")]
+            thread_pool: None
         }
     }
 }
@@ -42,7 +193,16 @@ where
         VoronoiBuilder {
             metric: PhantomData,
             sites: self.sites,
-            bounds: self.bounds
+            bounds: self.bounds,
+            duplicates: self.duplicates,
+            weights: self.weights,
+            rasterizer: self.rasterizer,
+            out_of_bounds: self.out_of_bounds,
+            ties: self.ties,
+            check_metric: self.check_metric,
+            instrument: self.instrument,
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool
         }
     }
 
@@ -52,50 +212,281 @@ where
         self
     }
 
-    pub fn build(self) -> VoronoiTesselation<S, M> {
-        let bounds = if let Some(value) = self.bounds {
+    pub fn duplicates(mut self, policy: DuplicatePolicy<S>) -> Self {
+        self.duplicates = policy;
+
+        self
+    }
+
+    // Sets what happens to sites with a weight the metric cannot use, by
+    // default the build fails
+    pub fn weights(mut self, policy: WeightPolicy<S>) -> Self {
+        self.weights = policy;
+
+        self
+    }
+
+    // Sets how sites are turned into seed cells, by default every site seeds
+    // the cell at its coordinates
+    pub fn rasterizer<R>(mut self, rasterizer: R) -> Self
+    where
+        R: SiteRasterizer<S> + 'static
+    {
+        self.rasterizer = Box::new(rasterizer);
+
+        self
+    }
+
+    pub fn out_of_bounds(mut self, policy: OutOfBoundsPolicy) -> Self {
+        self.out_of_bounds = policy;
+
+        self
+    }
+
+    pub fn ties(mut self, rule: TieBreak) -> Self {
+        self.ties = rule;
+
+        self
+    }
+
+    // Fails the build with VoronoiError::UnreliableMetric when diagnose()
+    // finds any problem with the metric and sites
+    pub fn check_metric(mut self, enabled: bool) -> Self {
+        self.check_metric = enabled;
+
+        self
+    }
+
+    // Gathers counters of the work done by every step, read back with
+    // VoronoiTesselation::counters()
+    pub fn instrument(mut self, enabled: bool) -> Self {
+        self.instrument = enabled;
+
+        self
+    }
+
+    // Runs the parallel parts of building and computing inside the pool
+    // instead of the global one
+    #[cfg(feature = "rayon")]
+    pub fn thread_pool(mut self, pool: Arc<rayon::ThreadPool>) -> Self {
+        self.thread_pool = Some(pool);
+
+        self
+    }
+
+    pub fn add_site(mut self, site: S) -> Self {
+        self.sites.push(site);
+
+        self
+    }
+
+    pub fn extend_sites<I>(mut self, sites: I) -> Self
+    where
+        I: IntoIterator<Item = S>
+    {
+        self.sites.extend(sites);
+
+        self
+    }
+
+    // Sites with the same coordinates are handled according to the duplicate
+    // policy, by default only the first one is kept
+    pub fn build(self) -> Result<VoronoiTesselation<S, M>, VoronoiError> {
+        let finite = |site: &S| {
+            let (x, y) = site.position();
+            x.is_finite() && y.is_finite()
+        };
+        if let Some(index) = self.sites.iter().position(|site| !finite(site)) {
+            return Err(VoronoiError::NonFinitePosition(index));
+        }
+        if let Some(index) = self.sites.iter().position(|site| site.checked_coordinates().is_none()) {
+            return Err(VoronoiError::CoordinatesOutOfRange(index));
+        }
+
+        let mut indexed: Vec<(usize, S)> = self.sites.into_iter().enumerate().collect();
+        indexed.sort_by_key(|(_, site)| site.coordinates());
+        let mut duplicates = Vec::new();
+        let sites = VoronoiBuilder::<S, M>::resolve_duplicates(indexed, self.duplicates, &mut duplicates)?;
+
+        if let Some((index, _)) = sites.iter().find(|(_, site)| !site.weight().to_f64().is_finite()) {
+            return Err(VoronoiError::NonFiniteWeight(*index));
+        }
+
+        let mut invalid_weights = Vec::new();
+        let sites = VoronoiBuilder::<S, M>::validate_weights(sites, self.weights, &mut invalid_weights)?;
+        invalid_weights.sort_unstable();
+        let (original_indices, sites): (Vec<usize>, Vec<S>) = sites.into_iter().unzip();
+
+        let mut bounds = if let Some(value) = self.bounds {
             value
         } else {
-            BoundingBox::fit_to_sites(&self.sites)
+            BoundingBox::fit_to_sites(&sites)?
         };
 
-        let num_sites = self.sites.len();
-        let sites_id_pars = self.sites
+        bounds.validate()?;
+
+        let rasterizer = &self.rasterizer;
+        let seeded: Vec<(usize, S, Vec<GridIdx>)> = original_indices
+            .into_iter()
+            .zip(sites)
+            .map(|(original_index, site)| {
+                let seeds = rasterizer.seed_cells(&site);
+                (original_index, site, seeds)
+            })
+            .collect();
+        let outside = |seeds: &[GridIdx], bounds: &BoundingBox| !seeds.iter().any(|seed| seed.inside(bounds));
+
+        match self.out_of_bounds {
+            OutOfBoundsPolicy::Error => {
+                if let Some((index, _, _)) = seeded.iter().find(|(_, _, seeds)| outside(seeds, &bounds)) {
+                    return Err(VoronoiError::SiteOutOfBounds(*index));
+                }
+            },
+            OutOfBoundsPolicy::Expand => {
+                let pulled_in = seeded.iter().filter(|(_, _, seeds)| outside(seeds, &bounds));
+                bounds = bounds.including(pulled_in.flat_map(|(_, _, seeds)| seeds.iter().copied()))?;
+            },
+            OutOfBoundsPolicy::Drop | OutOfBoundsPolicy::Clamp => {}
+        }
+
+        let num_sites = seeded.len();
+        check_site_count(num_sites)?;
+        let mut clipped = Vec::new();
+        let policy = self.out_of_bounds;
+        let sites_id_pars = seeded
             .into_iter()
-            .filter(|site| {
-                let idx = GridIdx::from(site.coordinates());
+            .filter_map(|(original_index, site, seeds)| {
+                if !outside(&seeds, &bounds) {
+                    return Some((original_index, site, seeds));
+                }
 
-                idx.inside(&bounds)
+                clipped.push(original_index);
+                match policy {
+                    OutOfBoundsPolicy::Clamp => {
+                        let seed = bounds.clamp_idx(GridIdx::from(site.coordinates()));
+                        Some((original_index, site, vec![seed]))
+                    },
+                    _ => None
+                }
             })
             .zip(0..(num_sites as u32));
         let wrapped_sites = sites_id_pars
-            .map(|(site, id)| (SiteOwner(id), SiteWrapper::new(id, site)));
+            .map(|((original_index, site, seeds), id)| SiteWrapper::new(id, original_index, site, seeds));
+
+        let sites_table = SiteTable(wrapped_sites.collect());
+        if sites_table.is_empty() {
+            return Err(VoronoiError::NoSitesInBounds);
+        }
+        clipped.sort_unstable();
 
-        let mut sites_map = HashMap::with_capacity(num_sites);
-        sites_map.extend(wrapped_sites);
         let mut tesselation = VoronoiTesselation {
-            sites: sites_map,
+            sites: sites_table,
             metric: PhantomData,
-            grid: Grid::new(bounds)
+            #[cfg(feature = "rayon")]
+            grid: in_pool(&self.thread_pool, || Grid::new(bounds)),
+            #[cfg(not(feature = "rayon"))]
+            grid: Grid::new(bounds),
+            frontier: Vec::new(),
+            steps: 0,
+            report: BuildReport { duplicates, clipped, invalid_weights },
+            ties: self.ties,
+            counters: if self.instrument { Some(ComputeCounters::default()) } else { None },
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool
         };
 
+        if self.check_metric && !tesselation.diagnose().is_empty() {
+            return Err(VoronoiError::UnreliableMetric);
+        }
+
         tesselation.init_sites();
 
-        tesselation
+        Ok(tesselation)
+    }
+
+    fn validate_weights(
+        sites: Vec<(usize, S)>,
+        policy: WeightPolicy<S>,
+        invalid: &mut Vec<usize>
+    ) -> Result<Vec<(usize, S)>, VoronoiError> {
+        let mut valid = Vec::with_capacity(sites.len());
+
+        for (index, site) in sites {
+            if M::valid_weight(site.weight().to_f64()) {
+                valid.push((index, site));
+                continue;
+            }
+
+            match policy {
+                WeightPolicy::Error => return Err(VoronoiError::InvalidWeight(index)),
+                WeightPolicy::Clamp(clamp) => {
+                    let site = clamp(site);
+                    if !M::valid_weight(site.weight().to_f64()) {
+                        return Err(VoronoiError::InvalidWeight(index));
+                    }
+
+                    invalid.push(index);
+                    valid.push((index, site));
+                },
+                WeightPolicy::Skip => invalid.push(index),
+                WeightPolicy::Allow => valid.push((index, site))
+            }
+        }
+
+        Ok(valid)
+    }
+
+    // Expects the sites to be sorted by coordinates, keeping input order
+    // between co-located sites
+    fn resolve_duplicates(
+        indexed: Vec<(usize, S)>,
+        policy: DuplicatePolicy<S>,
+        duplicates: &mut Vec<DuplicateSite>
+    ) -> Result<Vec<(usize, S)>, VoronoiError> {
+        let mut kept: Vec<(usize, S)> = Vec::with_capacity(indexed.len());
+
+        for (index, site) in indexed {
+            let duplicate_of = match kept.last() {
+                Some((kept_index, last)) if last.coordinates() == site.coordinates() => *kept_index,
+                _ => {
+                    kept.push((index, site));
+                    continue;
+                }
+            };
+
+            match policy {
+                DuplicatePolicy::KeepFirst => {},
+                DuplicatePolicy::MergeWeights(merge) => {
+                    let (_, last) = kept.pop().unwrap();
+                    kept.push((duplicate_of, merge(last, site)));
+                },
+                DuplicatePolicy::Error => return Err(VoronoiError::DuplicateSite(index)),
+                DuplicatePolicy::AllowDuplicates => {
+                    kept.push((index, site));
+                    continue;
+                }
+            }
+
+            duplicates.push(DuplicateSite { index, duplicate_of });
+        }
+
+        Ok(kept)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SiteOwner(pub u32);
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct SiteWrapper<S>
 where
     S: Site
 {
     id: SiteOwner,
-    newly_claimed: Vec<GridIdx>,
-    boundary_chain: Vec<GridIdx>,
+    // Position of the site in the builder input
+    original_index: usize,
+    // Cells seeded by the site, some of them may be outside of the bounds
+    seeds: Vec<GridIdx>,
     site: S
 }
 
@@ -103,33 +494,148 @@ impl<S> SiteWrapper<S>
 where
     S: Site
 {
-    fn new(id: u32, site: S) -> Self {
+    fn new(id: u32, original_index: usize, site: S, seeds: Vec<GridIdx>) -> Self {
         SiteWrapper {
             id: SiteOwner(id),
+            original_index,
             site,
-            newly_claimed: Vec::new(),
-            boundary_chain: Vec::new()
+            seeds
         }
     }
+}
+
+// Owner ids are packed into the cells next to their flags, so only so many
+// sites fit in a tesselation
+pub(crate) fn check_site_count(num_sites: usize) -> Result<(), VoronoiError> {
+    if num_sites < MAX_SITES { Ok(()) } else { Err(VoronoiError::TooManySites) }
+}
 
-    fn update_boundary_chain(&mut self, bounds: &BoundingBox) {
-        for idx in &self.newly_claimed {
-            self.boundary_chain.extend(idx.neighbors(bounds));
+// Neighbours of a frontier cell that its owner could claim
+// The closest candidate takes the cell if it is strictly closer than the
+// current owner, which has to come first among the candidates. Equal
+// distances go to the candidate that came first in the builder input, given
+// with every candidate, or leave the cell tied under TieBreak::Unowned.
+pub(crate) fn resolve_claims<'a, S, M, I>(
+    idx: GridIdx,
+    owner: Option<SiteOwner>,
+    candidates: I,
+    ties: TieBreak
+) -> Resolution
+where
+    S: Site + 'a,
+    M: Metric,
+    I: IntoIterator<Item = (SiteOwner, usize, &'a S)>
+{
+    let mut closest: Option<(SiteOwner, usize, M::Output)> = None;
+    let mut tied = false;
+    for (candidate, original_index, site) in candidates {
+        let distance = M::distance(site, &idx);
+        match closest {
+            Some((_, _, ref best)) if distance < *best => {
+                closest = Some((candidate, original_index, distance));
+                tied = false;
+            },
+            Some((_, winner_index, ref best)) if distance == *best => {
+                tied = true;
+                if original_index < winner_index {
+                    closest = Some((candidate, original_index, distance));
+                }
+            },
+            Some(_) => {},
+            None => closest = Some((candidate, original_index, distance))
         }
     }
+
+    match closest {
+        Some((winner, _, _)) if tied && ties == TieBreak::Unowned => Resolution::Tied(winner),
+        Some((winner, _, _)) if Some(winner) != owner => Resolution::Claimed(winner),
+        _ => Resolution::Kept
+    }
+}
+
+fn frontier_claims<'a>(
+    grid: &'a Grid,
+    entry: &'a (GridIdx, SiteOwner)
+) -> impl Iterator<Item = (GridIdx, SiteOwner)> + 'a {
+    let (idx, owner) = entry;
+    let owner = *owner;
+
+    idx.neighbors(grid.bounds())
+        .filter(move |&neighbor| grid[neighbor].owner() != Some(owner))
+        .map(move |neighbor| (neighbor, owner))
+}
+
+// Runs the closure inside the thread pool given to the builder, or on the
+// global pool without one
+#[cfg(feature = "rayon")]
+fn in_pool<R, F>(pool: &Option<Arc<rayon::ThreadPool>>, op: F) -> R
+where
+    R: Send,
+    F: FnOnce() -> R + Send
+{
+    match *pool {
+        Some(ref pool) => pool.install(op),
+        None => op()
+    }
 }
 
-impl<S> PartialEq for SiteWrapper<S> where S: Site {
-    fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+// Outcome of the claims on a single cell in one step
+pub(crate) enum Resolution {
+    Claimed(SiteOwner),
+    Kept,
+    // Left unowned, tied with the given site among others
+    Tied(SiteOwner)
+}
+
+// Sites of a tesselation stored densely, the owner ids handed out by the
+// builder are the positions in the table
+#[derive(Debug, Clone)]
+struct SiteTable<S>(Vec<SiteWrapper<S>>)
+where
+    S: Site;
+
+impl<S> SiteTable<S>
+where
+    S: Site
+{
+    fn get(&self, owner: SiteOwner) -> Option<&SiteWrapper<S>> {
+        self.0.get(owner.0 as usize)
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, SiteWrapper<S>> {
+        self.0.iter()
+    }
+
+    fn owners(&self) -> impl Iterator<Item = SiteOwner> + '_ {
+        self.0.iter().map(|wrapper| wrapper.id)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 }
 
-impl<S> Eq for SiteWrapper<S> where S: Site {}
+impl<S> Index<SiteOwner> for SiteTable<S>
+where
+    S: Site
+{
+    type Output = SiteWrapper<S>;
+
+    fn index(&self, owner: SiteOwner) -> &Self::Output {
+        &self.0[owner.0 as usize]
+    }
+}
 
-impl<S> Hash for SiteWrapper<S> where S: Site {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.id.hash(state);
+impl<S> IndexMut<SiteOwner> for SiteTable<S>
+where
+    S: Site
+{
+    fn index_mut(&mut self, owner: SiteOwner) -> &mut Self::Output {
+        &mut self.0[owner.0 as usize]
     }
 }
 
@@ -138,9 +644,18 @@ where
     S: Site,
     M: Metric
 {
-    sites: HashMap<SiteOwner, SiteWrapper<S>>,
+    sites: SiteTable<S>,
     metric: PhantomData<M>,
-    grid: Grid
+    grid: Grid,
+    // Cells claimed in the last step along with their new owner, the regions
+    // keep growing from here
+    frontier: Vec<(GridIdx, SiteOwner)>,
+    steps: u32,
+    report: BuildReport,
+    ties: TieBreak,
+    counters: Option<ComputeCounters>,
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<Arc<rayon::ThreadPool>>
 }
 
 impl<S, M> VoronoiTesselation<S, M>
@@ -148,192 +663,1847 @@ where
     S: Site,
     M: Metric
 {
-    pub fn sites(&self) -> Vec<&S> {
-        self.sites.iter().map(|(_, wrapper)| &wrapper.site).collect()
+    // Sites sorted by owner id
+    pub fn sites(&self) -> impl Iterator<Item = &S> + '_ {
+        self.sites.iter().map(|wrapper| &wrapper.site)
+    }
+
+    pub fn sites_vec(&self) -> Vec<&S> {
+        self.sites().collect()
+    }
+
+    // Number of sites in the tesselation
+    pub fn len(&self) -> usize {
+        self.sites.len()
+    }
+
+    // Always false, a tesselation cannot be built without sites
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
     }
 
     pub fn bounds(&self) -> &BoundingBox {
         self.grid.bounds()
     }
 
-    pub fn init_sites(&mut self) {
-        for (_, site_wrapper) in self.sites.iter_mut() {
-            let mut to_claim = vec![GridIdx::from(site_wrapper.site.coordinates())];
-            let (claimed, contested) = self.grid.claim_cells(&to_claim, site_wrapper.id);
+    // Cells in row-major order, starting from the top left corner of the bounds
+    pub fn cells(&self) -> Cells<'_> {
+        self.grid.cells()
+    }
 
-            debug_assert_eq!(claimed.len(), 1);
-            debug_assert!(contested.is_empty());
+    // Sites paired with their owner ids, sorted by id
+    pub(crate) fn owned_sites(&self) -> Vec<(SiteOwner, &S)> {
+        self.sites.iter().map(|wrapper| (wrapper.id, &wrapper.site)).collect()
+    }
+
+    pub fn site(&self, owner: SiteOwner) -> Option<&S> {
+        self.sites.get(owner).map(|wrapper| &wrapper.site)
+    }
 
-            site_wrapper.newly_claimed.append(&mut to_claim);
+    // Cell at the coordinates, None if they are outside of the bounds
+    pub fn get<I: Into<GridIdx>>(&self, idx: I) -> Option<&Cell> {
+        let idx = idx.into();
+        if idx.inside(self.grid.bounds()) {
+            Some(&self.grid[idx])
+        } else {
+            None
         }
     }
 
-    pub fn reset_grid(&mut self) {
-        self.grid.clear()
+    // Step in which the current owner claimed the cell at the coordinates, None
+    // if the cell is unowned or outside of the bounds
+    pub fn claim_step<I: Into<GridIdx>>(&self, idx: I) -> Option<u32> {
+        let position = self.grid.bounds().linear_idx(idx.into())?;
+        self.grid.cells().claim_step(position)
     }
 
-    pub fn compute(&mut self) {
-        while self.sum_newly_claimed() > 0 {
-            self.step();
+    // Owner of the cell at the coordinates, None if the cell is unowned or
+    // outside of the bounds
+    pub fn owner_at(&self, coordinates: (isize, isize)) -> Option<(SiteOwner, &S)> {
+        self.get(coordinates)?.owner().map(|owner| (owner, &self.sites[owner].site))
+    }
+
+    // Closest site to the point under the metric, computed directly from the
+    // sites so it works anywhere and before compute(). Ties go to the lower id.
+    pub fn nearest_site<P: Point>(&self, point: &P) -> (&S, M::Output) {
+        let mut nearest: Option<(SiteOwner, &S, M::Output)> = None;
+        for wrapper in self.sites.iter() {
+            let distance = M::distance(&wrapper.site, point);
+            let closer = match nearest {
+                Some((best_owner, _, ref best_distance)) => {
+                    distance < *best_distance || (distance == *best_distance && wrapper.id.0 < best_owner.0)
+                },
+                None => true
+            };
+
+            if closer {
+                nearest = Some((wrapper.id, &wrapper.site, distance));
+            }
         }
+
+        let (_, site, distance) = nearest.expect("A tesselation always has at least one site");
+        (site, distance)
     }
 
-    pub fn step(&mut self) {
-        let keys: Vec<SiteOwner> = self.sites.keys().cloned().collect();
-        for site_wrapper_idx in keys {
-            let site_wrapper = self.sites.get_mut(&site_wrapper_idx).unwrap();
+    // Cells owned by the site in row-major order, empty for unknown owners
+    pub fn region_of(&self, owner: SiteOwner) -> impl Iterator<Item = (GridIdx, &Cell)> + '_ {
+        self.grid.cells().into_iter().filter(move |(_, cell)| cell.owner() == Some(owner))
+    }
 
-            site_wrapper.boundary_chain.clear();
-            site_wrapper.update_boundary_chain(self.grid.bounds());
+    // Ids of every site in the tesselation, sorted by id
+    pub fn owners(&self) -> impl Iterator<Item = SiteOwner> + '_ {
+        self.sites.owners()
+    }
 
-            site_wrapper.newly_claimed.clear();
-            let (mut claimed, contested) = self.grid
-                .claim_cells(&site_wrapper.boundary_chain, site_wrapper.id);
+    // Index of the site in the input given to the builder, owners are assigned
+    // after sorting and filtering so they do not follow the input order
+    pub fn original_index(&self, owner: SiteOwner) -> Option<usize> {
+        self.sites.get(owner).map(|wrapper| wrapper.original_index)
+    }
 
-            site_wrapper.newly_claimed.append(&mut claimed);
+    // Sites dropped or merged by the duplicate policy when building
+    pub fn duplicate_sites(&self) -> &[DuplicateSite] {
+        &self.report.duplicates
+    }
 
-            let mut claimed_won = VoronoiTesselation::<S, M>::handle_conflicts(
-                &self.sites,
-                &site_wrapper_idx,
-                contested,
-                &mut self.grid
-            );
+    // Input sites that were dropped, merged or clipped when building
+    pub fn build_report(&self) -> &BuildReport {
+        &self.report
+    }
 
-            self.sites.get_mut(&site_wrapper_idx).unwrap().newly_claimed.append(&mut claimed_won);
+    pub fn init_sites(&mut self) {
+        let owners: Vec<SiteOwner> = self.sites.owners().collect();
+        for owner in owners {
+            self.seed_site(owner, 0);
         }
     }
 
-    fn handle_conflicts(
-        sites: &HashMap<SiteOwner, SiteWrapper<S>>,
-        owner_idx: &SiteOwner,
-        contested: Vec<(GridIdx, SiteOwner)>,
-        grid: &mut Grid
-    ) -> Vec<GridIdx> {
-        let mut claimed = Vec::new();
-        for (idx, old_owner) in contested.into_iter() {
-            let our_distance = M::distance(&sites[owner_idx].site, &idx);
-            let their_distance = M::distance(&sites[&old_owner].site, &idx);
+    // Claims the seed cells of the site that are inside the bounds. Only
+    // overlapping seeds can find a cell already taken, in that case the closer
+    // site wins and ties stay with the earlier owner.
+    fn seed_site(&mut self, owner: SiteOwner, step: u32) {
+        let bounds = *self.grid.bounds();
+        let seeds = std::mem::take(&mut self.sites[owner].seeds);
 
-            if their_distance > our_distance {
-                claimed.push(idx);
-                grid[idx].set_owner(sites[owner_idx].id);
-            } else if their_distance == our_distance {
+        for &idx in seeds.iter().filter(|seed| seed.inside(&bounds)) {
+            if let Some(other) = self.grid[idx].owner() {
+                let our_distance = M::distance(&self.sites[owner].site, &idx);
+                let their_distance = M::distance(&self.sites[other].site, &idx);
+                if other == owner || their_distance <= our_distance {
+                    continue;
+                }
 
-            } else {
-                grid[idx].set_owner(old_owner)
+                self.frontier.retain(|&claimed| claimed != (idx, other));
             }
+
+            self.grid.set_owner(idx, owner, step);
+            self.frontier.push((idx, owner));
         }
 
-        claimed
+        self.sites[owner].seeds = seeds;
     }
 
-    fn sum_newly_claimed(&self) -> usize {
-        self.sites
-            .iter()
-            .map(|(_, site_wrapper)| site_wrapper.newly_claimed.len())
-            .sum()
+    pub fn reset_grid(&mut self) {
+        self.steps = 0;
+        if let Some(ref mut counters) = self.counters {
+            *counters = ComputeCounters::default();
+        }
+        #[cfg(feature = "rayon")]
+        {
+            let grid = &mut self.grid;
+            in_pool(&self.thread_pool, || grid.clear());
+        }
+        #[cfg(not(feature = "rayon"))]
+        self.grid.clear()
     }
 
-    pub fn into_buffer<F, T>(self, mut map: F) -> Vec<T>
-    where
-        F: FnMut(&Cell, Option<&S>) -> T
-    {
-        let sites = self.sites;
-        self.grid
-            .into_raw()
-            .into_iter()
-            .map(|cell| match cell.owner() {
-                &Some(owner) => map(cell, Some(&sites[&owner].site)),
-                &None => map(cell, None)
-            })
-            .collect()
+    // Rule used to settle cells at the same distance from more than one site
+    pub fn tie_break(&self) -> TieBreak {
+        self.ties
     }
 
-    pub fn into_regions(self) -> HashMap<S, Vec<Cell>> where S: Eq + Hash + Clone {
-        let mut regions = HashMap::new();
+    // Work done since the grid was last reset, None unless the builder was
+    // asked to instrument the computation
+    pub fn counters(&self) -> Option<&ComputeCounters> {
+        self.counters.as_ref()
+    }
 
-        let cells: Vec<Cell> = From::from(self.grid.into_raw());
-        for cell in cells.into_iter() {
-            if cell.owner().is_some() {
-                let owner = cell.owner().as_ref().unwrap();
-                let ref site_wrapper = self.sites[owner];
-                if !regions.contains_key(&site_wrapper.site) {
-                    regions.insert(site_wrapper.site.clone(), Vec::new());
-                }
-                regions.get_mut(&site_wrapper.site).unwrap().push(cell);
-            }
-        }
+    // Number of steps taken by the computation so far
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    // Clears all ownership and places the sites again, ready for compute()
+    fn reseed(&mut self) {
+        self.reset_grid();
+        self.frontier.clear();
 
-        regions
+        self.init_sites();
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use metric::MultWeightedEuclidean;
+    // Reuses the sites and grid allocation with a different metric, the
+    // returned tesselation is reseeded and needs to be computed again. Fails
+    // like the builder under WeightPolicy::Error if a site has a weight the
+    // new metric cannot use.
+    pub fn with_metric<E: Metric>(self) -> Result<VoronoiTesselation<S, E>, VoronoiError> {
+        if let Some(wrapper) = self.sites.iter().find(|wrapper| !E::valid_weight(wrapper.site.weight().to_f64())) {
+            return Err(VoronoiError::InvalidWeight(wrapper.original_index));
+        }
 
-    #[test]
-    fn build_voronoi_tesselation() {
-        let sites: Vec<(isize, isize, f32)> = vec![
-            (1, 1, 1f32),
-            (2, 2, 2f32),
-            (3, 3, 3f32),
-            (4, 4, 4f32),
-            (5, 5, 5f32),
-            (6, 6, 6f32),
-        ];
+        let mut tesselation = VoronoiTesselation {
+            sites: self.sites,
+            metric: PhantomData,
+            grid: self.grid,
+            frontier: self.frontier,
+            steps: 0,
+            report: self.report,
+            ties: self.ties,
+            counters: self.counters,
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool
+        };
 
-        let tess = VoronoiBuilder::new(sites).build();
+        tesselation.reseed();
 
-        assert_eq!(tess.sites().len(), 6);
+        Ok(tesselation)
     }
 
-    #[test]
-    fn build_voronoi_clip_sites() {
-        let sites: Vec<(isize, isize, f32)> = vec![
-            (1, 1, 1f32),
-            (2, 2, 2f32),
-            (3, 3, 3f32),
-            (4, 4, 4f32),
-            (5, 5, 5f32),
-            (6, 6, 6f32),
-        ];
+    // Grows the grid to the new bounds, keeping the ownership of the existing
+    // cells. Regions touching the old border are set up to keep growing into
+    // the new cells on the next compute(). Sites that were clipped when
+    // building are not brought back.
+    pub fn expand_bounds(&mut self, bounds: BoundingBox) -> Result<(), VoronoiError> {
+        bounds.validate()?;
+        let old_bounds = *self.grid.bounds();
+        if !bounds.contains(&old_bounds) {
+            return Err(VoronoiError::BoundsNotContained);
+        }
 
-        let builder = VoronoiBuilder::new(sites).bounds(BoundingBox::new(2, 2, 3, 3));
+        self.grid.expand(bounds);
 
-        let tess = builder.build();
+        for idx in old_bounds.coordinates_iter() {
+            if let Some(owner) = self.grid[idx].owner() {
+                let on_border = idx.neighbors(&bounds).any(|neighbor| !neighbor.inside(&old_bounds));
+                if on_border {
+                    self.frontier.push((idx, owner));
+                }
+            }
+        }
 
-        assert_eq!(tess.sites().len(), 3);
+        Ok(())
     }
 
-    #[test]
-    fn compute_discrete_voronoi() {
-        let sites: Vec<(isize, isize, f32)> = vec![
-            (0, 0, 1f32),
-            (1, 1, 1f32),
-            (2, 2, 1f32),
-            (3, 3, 1f32),
-            (4, 4, 1f32),
-            (5, 5, 1f32),
-            (6, 6, 1f32),
-        ];
+    // Throws away the current result and computes the tesselation from scratch
+    pub fn recompute(&mut self) {
+        self.reseed();
+        self.compute();
+    }
 
-        let mut tess = VoronoiBuilder::new(sites).build();
+    // Changes the weight of a site and repairs the computed tesselation around
+    // it. Only the region of the site is released, the neighbouring regions
+    // grow back into it while the site grows again from its seed, taking over
+    // any cells it is now closer to. Cells further away keep their owners.
+    pub fn set_weight(&mut self, owner: SiteOwner, weight: S::Weight) -> Result<(), VoronoiError>
+    where
+        S: SiteMut
+    {
+        let wrapper = self.sites.get(owner).ok_or(VoronoiError::UnknownSite(owner))?;
+        if !weight.to_f64().is_finite() {
+            return Err(VoronoiError::NonFiniteWeight(wrapper.original_index));
+        }
+        if !M::valid_weight(weight.to_f64()) {
+            return Err(VoronoiError::InvalidWeight(wrapper.original_index));
+        }
 
-        tess.compute();
-    }
+        // The repair relies on every region having stopped growing
+        self.compute();
+        self.sites[owner].site.set_weight(weight);
+
+        let bounds = *self.grid.bounds();
+        let released: Vec<GridIdx> = bounds
+            .coordinates_iter()
+            .filter(|&idx| self.grid[idx].owner().or(self.grid[idx].tied_with()) == Some(owner))
+            .collect();
+        for &idx in &released {
+            self.grid.reset(idx);
+        }
+
+        for &idx in &released {
+            for neighbor in idx.neighbors(&bounds) {
+                match self.grid[neighbor].owner() {
+                    Some(other) if other != owner => self.frontier.push((neighbor, other)),
+                    Some(_) => {},
+                    // Cells left unowned by a tie may now have a clear winner,
+                    // the tie partners around them have to claim them again
+                    None if self.grid[neighbor].tied_with().is_some() => {
+                        self.grid.reset(neighbor);
+                        for partner_cell in neighbor.neighbors(&bounds) {
+                            if let Some(partner) = self.grid[partner_cell].owner().filter(|&other| other != owner) {
+                                self.frontier.push((partner_cell, partner));
+                            }
+                        }
+                    },
+                    None => {}
+                }
+            }
+        }
+
+        self.frontier.sort_unstable();
+        self.frontier.dedup();
+
+        self.seed_site(owner, self.steps);
+
+        self.compute();
+
+        Ok(())
+    }
+
+    // Computes the tesselation and merges every region with fewer cells than
+    // the minimum into a neighbouring region, smallest regions first. The
+    // merged sites are removed from the tesselation and the remaining sites
+    // get new owner ids in the same order, so ids handed out before are stale
+    // afterwards. Regions without any neighbour are kept as they are.
+    pub fn merge_small_regions(&mut self, min_cells: usize, target: MergeTarget) -> Vec<MergedRegion> {
+        self.compute();
+
+        let mut regions: Vec<Vec<GridIdx>> = vec![Vec::new(); self.sites.len()];
+        for (idx, cell) in self.grid.cells() {
+            if let Some(owner) = cell.owner() {
+                regions[owner.0 as usize].push(idx);
+            }
+        }
+
+        let bounds = *self.grid.bounds();
+        let mut merged_into: Vec<Option<SiteOwner>> = vec![None; self.sites.len()];
+        let mut isolated = vec![false; self.sites.len()];
+        let mut merges = Vec::new();
+
+        loop {
+            let small = self
+                .sites
+                .owners()
+                .filter(|&owner| {
+                    let position = owner.0 as usize;
+                    merged_into[position].is_none() && !isolated[position] && regions[position].len() < min_cells
+                })
+                .min_by_key(|&owner| (regions[owner.0 as usize].len(), owner));
+            let small = match small {
+                Some(small) => small,
+                None => break
+            };
+
+            let mut neighbors: Vec<SiteOwner> = regions[small.0 as usize]
+                .iter()
+                .flat_map(|idx| idx.neighbors(&bounds))
+                .filter_map(|neighbor| self.grid[neighbor].owner())
+                .filter(|&other| other != small)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+
+            let chosen = match target {
+                MergeTarget::Largest => neighbors
+                    .iter()
+                    .copied()
+                    .max_by_key(|&other| (regions[other.0 as usize].len(), Reverse(other))),
+                MergeTarget::Nearest => {
+                    let site = &self.sites[small].site;
+                    neighbors.iter().copied().min_by(|&a, &b| {
+                        let a_distance = M::distance(&self.sites[a].site, site);
+                        let b_distance = M::distance(&self.sites[b].site, site);
+                        a_distance.partial_cmp(&b_distance).unwrap_or(Ordering::Equal)
+                    })
+                }
+            };
+            let chosen = match chosen {
+                Some(chosen) => chosen,
+                None => {
+                    isolated[small.0 as usize] = true;
+                    continue;
+                }
+            };
+
+            let cells = std::mem::take(&mut regions[small.0 as usize]);
+            for &idx in &cells {
+                self.grid[idx].restore_owner(chosen);
+            }
+            regions[chosen.0 as usize].extend(cells);
+            merged_into[small.0 as usize] = Some(chosen);
+            merges.push(small);
+        }
+
+        if merges.is_empty() {
+            return Vec::new();
+        }
+
+        // Follow chains of merges to the region that absorbed them in the end
+        let last_owner = |mut owner: SiteOwner| {
+            while let Some(next) = merged_into[owner.0 as usize] {
+                owner = next;
+            }
+            owner
+        };
+
+        let mut renumbered = vec![SiteOwner(0); self.sites.len()];
+        let mut next_id = 0;
+        for owner in self.sites.owners() {
+            if merged_into[owner.0 as usize].is_none() {
+                renumbered[owner.0 as usize] = SiteOwner(next_id);
+                next_id += 1;
+            }
+        }
+        let remap = |owner: SiteOwner| renumbered[last_owner(owner).0 as usize];
+
+        let report = merges
+            .iter()
+            .map(|&small| MergedRegion {
+                index: self.sites[small].original_index,
+                merged_into: self.sites[last_owner(small)].original_index
+            })
+            .collect();
+
+        for idx in bounds.coordinates_iter() {
+            let cell = &mut self.grid[idx];
+            if let Some(owner) = cell.owner() {
+                cell.restore_owner(remap(owner));
+            } else if let Some(tied) = cell.tied_with() {
+                cell.mark_tied(remap(tied));
+            }
+        }
+        for entry in &mut self.frontier {
+            entry.1 = remap(entry.1);
+        }
+
+        self.sites.0.retain(|wrapper| merged_into[wrapper.id.0 as usize].is_none());
+        for wrapper in &mut self.sites.0 {
+            wrapper.id = renumbered[wrapper.id.0 as usize];
+        }
+
+        report
+    }
+
+    // Computes the tesselation and smooths jagged borders by handing every cell
+    // to the owner holding the most of the 3x3 block around it, for up to the
+    // given number of passes over the grid. A cell only changes owner if it
+    // touches the new region along an edge, is not a seed of its site and its
+    // old region stays connected without it. Returns the number of changes.
+    pub fn majority_filter(&mut self, iterations: usize) -> usize {
+        self.compute();
+
+        let bounds = *self.grid.bounds();
+        let mut changed = 0;
+
+        for _ in 0..iterations {
+            let mut changed_in_pass = 0;
+
+            for idx in bounds.coordinates_iter() {
+                let owner = match self.grid[idx].owner() {
+                    Some(owner) => owner,
+                    None => continue
+                };
+                if self.sites[owner].seeds.contains(&idx) {
+                    continue;
+                }
+
+                // Surrounding cells clockwise from the one above
+                let (x, y) = idx.coordinates();
+                let offsets = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+                let ring: Vec<Option<SiteOwner>> = offsets
+                    .iter()
+                    .map(|&(dx, dy)| {
+                        let neighbor = GridIdx::from((x.checked_add(dx)?, y.checked_add(dy)?));
+                        if neighbor.inside(&bounds) { self.grid[neighbor].owner() } else { None }
+                    })
+                    .collect();
+
+                let mut votes: Vec<(SiteOwner, usize)> = vec![(owner, 1)];
+                for &other in ring.iter().flatten() {
+                    match votes.iter_mut().find(|(voted, _)| *voted == other) {
+                        Some(vote) => vote.1 += 1,
+                        None => votes.push((other, 1))
+                    }
+                }
+                let own_votes = votes[0].1;
+                let winner = votes
+                    .iter()
+                    .filter(|&&(_, count)| count > own_votes)
+                    .filter(|&&(other, _)| ring.iter().step_by(2).any(|&edge| edge == Some(other)))
+                    .max_by_key(|&&(other, count)| (count, Reverse(other)));
+                let winner = match winner {
+                    Some(&(winner, _)) => winner,
+                    None => continue
+                };
+
+                // The old region stays connected if the cells it keeps around
+                // this one form a single run touching an edge
+                let runs = (0..8)
+                    .filter(|&position| ring[position] == Some(owner) && ring[(position + 7) % 8] != Some(owner))
+                    .filter(|&position| {
+                        (position..position + 8)
+                            .take_while(|&run| ring[run % 8] == Some(owner))
+                            .any(|run| run % 2 == 0)
+                    })
+                    .count();
+                if runs != 1 {
+                    continue;
+                }
+
+                self.grid[idx].restore_owner(winner);
+                changed_in_pass += 1;
+            }
+
+            changed += changed_in_pass;
+            if changed_in_pass == 0 {
+                break;
+            }
+        }
+
+        changed
+    }
+
+    // Computes the tesselation and settles every cell by the majority of an
+    // n x n grid of points spread over it, measured against the exact site
+    // positions, which removes the staircase along borders on coarse grids.
+    // The current owner keeps the cell when it ties for the majority. Returns
+    // the number of points per owner of every cell in row-major order.
+    pub fn supersample(&mut self, samples: usize) -> Vec<Vec<(SiteOwner, usize)>> {
+        self.compute();
+
+        let samples = samples.max(1);
+        let bounds = *self.grid.bounds();
+        let histograms: Vec<Vec<(SiteOwner, usize)>> =
+            bounds.coordinates_iter().map(|idx| sample_cell(self, idx, samples)).collect();
+
+        for (idx, histogram) in bounds.coordinates_iter().zip(&histograms) {
+            let current = self.grid[idx].owner();
+            let most = histogram.iter().map(|&(_, count)| count).max();
+            let majority = histogram
+                .iter()
+                .filter(|&&(_, count)| Some(count) == most)
+                .map(|&(owner, _)| owner)
+                .min_by_key(|&owner| (Some(owner) != current, owner));
+
+            if let Some(owner) = majority.filter(|&owner| Some(owner) != current) {
+                self.grid[idx].restore_owner(owner);
+            }
+        }
+
+        histograms
+    }
+
+    pub fn compute(&mut self) {
+        while !self.frontier.is_empty() {
+            self.step();
+        }
+    }
+
+    // Looks for sites and weights where growing regions does not give the
+    // true tesselation under the metric. Compares every pair of sites, so it
+    // is meant for checking a configuration rather than every run.
+    pub fn diagnose(&self) -> Vec<MetricWarning> {
+        let mut warnings = Vec::new();
+
+        for wrapper in self.sites.iter() {
+            let idx = GridIdx::from(wrapper.site.coordinates());
+            let own_distance = M::distance(&wrapper.site, &idx);
+            if self.sites.iter().any(|other| M::distance(&other.site, &idx) < own_distance) {
+                warnings.push(MetricWarning::SiteOutsideRegion(wrapper.id));
+            }
+        }
+
+        // Borders bending by less than a cell across the bounds rasterize like
+        // straight ones
+        if !M::connected_regions() {
+            let (width, height) = self.grid.bounds().dimensions();
+            let length = (width as f64).hypot(height as f64);
+            for (position, first) in self.sites.iter().enumerate() {
+                for second in self.sites.iter().skip(position + 1) {
+                    if M::border_bend(&first.site, &second.site, length) >= 1.0 {
+                        warnings.push(MetricWarning::DisconnectedRegions(first.id, second.id));
+                    }
+                }
+            }
+        }
+
+        warnings
+    }
+
+    // Checks every stride-th cell in row-major order against the closest site
+    // under the metric, measuring it against all sites. A cell passes if its
+    // owner is at the closest distance, or if it was left unowned by a tie
+    // under TieBreak::Unowned. Keeps the worst mismatches, up to the limit.
+    pub fn verify(&self, stride: usize, worst: usize) -> Verification
+    where
+        M::Output: Into<f64>
+    {
+        let mut verification = Verification {
+            checked: 0,
+            mismatches: 0,
+            worst: Vec::new()
+        };
+
+        for (idx, cell) in self.grid.cells().iter().step_by(stride.max(1)) {
+            verification.checked += 1;
+
+            let mut nearest: Option<(SiteOwner, f64)> = None;
+            let mut tied = false;
+            for wrapper in self.sites.iter() {
+                let distance: f64 = M::distance(&wrapper.site, &idx).into();
+                match nearest {
+                    Some((_, best)) if distance < best => {
+                        nearest = Some((wrapper.id, distance));
+                        tied = false;
+                    },
+                    Some((_, best)) => tied |= distance == best,
+                    None => nearest = Some((wrapper.id, distance))
+                }
+            }
+            let (nearest, nearest_distance) = nearest.expect("A tesselation always has at least one site");
+
+            let excess = match cell.owner() {
+                Some(owner) => M::distance(&self.sites[owner].site, &idx).into() - nearest_distance,
+                None if tied && self.ties == TieBreak::Unowned => 0.0,
+                None => f64::INFINITY
+            };
+            if excess > 0.0 {
+                verification.mismatches += 1;
+                verification.worst.push(Mismatch {
+                    coordinates: idx,
+                    owner: cell.owner(),
+                    nearest,
+                    excess
+                });
+                verification.worst.sort_by(|a, b| b.excess.total_cmp(&a.excess));
+                verification.worst.truncate(worst);
+            }
+        }
+
+        verification
+    }
+
+    // Assigns every cell to its closest site by measuring it against all of
+    // them, correct for any metric but much slower than compute(). Cells are
+    // marked as claimed in step 0 and ties follow the tie break rule.
+    pub fn compute_exact(&mut self) {
+        self.reset_grid();
+        self.frontier.clear();
+
+        let owners: Vec<SiteOwner> = self.sites.owners().collect();
+        let bounds = *self.grid.bounds();
+        for idx in bounds.coordinates_iter() {
+            match self.resolve_claims(idx, None, &owners) {
+                Resolution::Claimed(owner) => self.grid.set_owner(idx, owner, 0),
+                Resolution::Tied(tied_with) => self.grid.mark_tied(idx, tied_with),
+                Resolution::Kept => {}
+            }
+        }
+    }
+
+    // Every frontier cell offers its neighbours to its owner, gathered from
+    // the grid as it was at the start of the step, in parallel with the rayon
+    // feature. Each offered cell is then settled once from all of its claims,
+    // so neither the order of the sites nor the number of threads changes
+    // the result.
+    pub fn step(&mut self) {
+        self.steps += 1;
+
+        let started = self.counters.map(|_| Instant::now());
+        let grid = &self.grid;
+        #[cfg(feature = "rayon")]
+        let mut claims: Vec<(GridIdx, SiteOwner)> = {
+            use rayon::prelude::*;
+
+            let frontier = &self.frontier;
+            in_pool(&self.thread_pool, || {
+                frontier.par_iter().flat_map_iter(|entry| frontier_claims(grid, entry)).collect()
+            })
+        };
+        #[cfg(not(feature = "rayon"))]
+        let mut claims: Vec<(GridIdx, SiteOwner)> = self.frontier
+            .iter()
+            .flat_map(|entry| frontier_claims(grid, entry))
+            .collect();
+        claims.sort_unstable();
+        claims.dedup();
+
+        let mut step_counters = ComputeCounters {
+            steps: 1,
+            cells_scanned: match self.counters {
+                Some(_) => self.frontier.iter().map(|(idx, _)| idx.neighbors(self.grid.bounds()).count()).sum(),
+                None => 0
+            },
+            claims: claims.len(),
+            ..ComputeCounters::default()
+        };
+        let gathered = started.map(|_| Instant::now());
+
+        self.frontier.clear();
+        for cell_claims in claims.chunk_by(|a, b| a.0 == b.0) {
+            let idx = cell_claims[0].0;
+            let claimants: Vec<SiteOwner> = cell_claims.iter().map(|&(_, claimant)| claimant).collect();
+
+            // Claims on a tied cell have to beat the distance of the tie
+            let previous_owner = self.grid[idx].owner().or(self.grid[idx].tied_with());
+            if previous_owner.is_some() || claimants.len() > 1 {
+                self.grid[idx].mark_contested();
+                step_counters.conflicts_resolved += 1;
+            }
+
+            match self.resolve_claims(idx, previous_owner, &claimants) {
+                Resolution::Claimed(owner) => {
+                    self.grid.set_owner(idx, owner, self.steps);
+                    self.frontier.push((idx, owner));
+                    step_counters.cells_claimed += 1;
+                },
+                Resolution::Kept => {},
+                Resolution::Tied(tied_with) => {
+                    self.grid.mark_tied(idx, tied_with);
+                    step_counters.ties += 1;
+                }
+            }
+        }
+
+        if let (Some(counters), Some(started), Some(gathered)) = (self.counters.as_mut(), started, gathered) {
+            step_counters.gather_time = gathered - started;
+            step_counters.resolve_time = gathered.elapsed();
+            *counters += step_counters;
+        }
+    }
+
+    fn resolve_claims(&self, idx: GridIdx, owner: Option<SiteOwner>, claimants: &[SiteOwner]) -> Resolution {
+        // The current owner goes first so it keeps the cell on ties it wins
+        let candidates = owner.iter().chain(claimants).map(|&candidate| {
+            let wrapper = &self.sites[candidate];
+            (candidate, wrapper.original_index, &wrapper.site)
+        });
+
+        resolve_claims::<S, M, _>(idx, owner, candidates, self.ties)
+    }
+
+    // True once no site can claim any more cells
+    pub fn is_complete(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut region_sizes: Vec<(SiteOwner, usize)> = self.sites.owners().map(|owner| (owner, 0)).collect();
+        let mut unclaimed = 0;
+        let mut contested = 0;
+
+        for (_, cell) in self.grid.cells() {
+            match cell.owner() {
+                Some(owner) => region_sizes[owner.0 as usize].1 += 1,
+                None => unclaimed += 1
+            }
+
+            if cell.contested() {
+                contested += 1;
+            }
+        }
+
+        Summary {
+            bounds: *self.bounds(),
+            sites: self.sites.len(),
+            region_sizes,
+            unclaimed,
+            contested,
+            steps: self.steps,
+            complete: self.is_complete()
+        }
+    }
+
+    pub fn into_buffer<F, T>(self, mut map: F) -> Vec<T>
+    where
+        F: FnMut(GridIdx, &Cell, Option<&S>) -> T
+    {
+        self.grid
+            .cells()
+            .into_iter()
+            .map(|(idx, cell)| map(idx, cell, cell.owner().map(|owner| &self.sites[owner].site)))
+            .collect()
+    }
+
+    // Visits every cell in row-major order along with its owning site
+    pub fn for_each_cell<F>(&self, mut f: F)
+    where
+        F: FnMut(GridIdx, &Cell, Option<&S>)
+    {
+        for (idx, cell) in self.grid.cells() {
+            f(idx, cell, cell.owner().map(|owner| &self.sites[owner].site));
+        }
+    }
+
+    // Like into_buffer(), but fills storage owned by the caller, which must
+    // have exactly one element per cell
+    pub fn write_buffer_into<F, T>(&self, buffer: &mut [T], mut map: F) -> Result<(), VoronoiError>
+    where
+        F: FnMut(GridIdx, &Cell, Option<&S>) -> T
+    {
+        let cells = self.grid.cells();
+        if buffer.len() != cells.len() {
+            return Err(VoronoiError::BufferLength {
+                expected: cells.len(),
+                actual: buffer.len()
+            });
+        }
+
+        for (slot, (idx, cell)) in buffer.iter_mut().zip(cells) {
+            *slot = map(idx, cell, cell.owner().map(|owner| &self.sites[owner].site));
+        }
+
+        Ok(())
+    }
+
+    // Streams the bytes produced for every cell in row-major order to the
+    // writer, without holding the whole buffer in memory
+    pub fn write_buffer<W, F, B>(&self, mut writer: W, mut map: F) -> io::Result<()>
+    where
+        W: io::Write,
+        F: FnMut(GridIdx, &Cell, Option<&S>) -> B,
+        B: AsRef<[u8]>
+    {
+        for (idx, cell) in self.grid.cells() {
+            writer.write_all(map(idx, cell, cell.owner().map(|owner| &self.sites[owner].site)).as_ref())?;
+        }
+
+        writer.flush()
+    }
+
+    pub fn into_regions(self) -> HashMap<S, Vec<(GridIdx, Cell)>> where S: Eq + Hash + Clone {
+        let (sites, regions) = self.split_regions();
+
+        let mut by_site: HashMap<S, Vec<(GridIdx, Cell)>> = HashMap::with_capacity(regions.len());
+        for (wrapper, region) in sites.0.into_iter().zip(regions) {
+            if !region.is_empty() {
+                by_site.entry(wrapper.site).or_default().extend(region);
+            }
+        }
+
+        by_site
+    }
+
+    // Cells of every region keyed by owner, for site types that cannot be
+    // hashed or cloned. Look the sites up with site() before consuming the
+    // tesselation, or use into_iter() to get them along with the cells.
+    pub fn into_regions_by_owner(self) -> HashMap<SiteOwner, Vec<(GridIdx, Cell)>> {
+        let (sites, regions) = self.split_regions();
+
+        sites
+            .owners()
+            .zip(regions)
+            .filter(|(_, region)| !region.is_empty())
+            .collect()
+    }
+
+    // Moves the cells into one vector per owner, indexed by owner id. The
+    // vectors are sized up front from a first counting pass.
+    fn split_regions(self) -> (SiteTable<S>, Vec<Vec<(GridIdx, Cell)>>) {
+        let mut sizes = vec![0; self.sites.len()];
+        for owner in self.grid.cells().iter().filter_map(|(_, cell)| cell.owner()) {
+            sizes[owner.0 as usize] += 1;
+        }
+
+        let mut regions: Vec<Vec<(GridIdx, Cell)>> = sizes.into_iter().map(Vec::with_capacity).collect();
+        for (idx, &cell) in self.grid.cells() {
+            if let Some(owner) = cell.owner() {
+                regions[owner.0 as usize].push((idx, cell));
+            }
+        }
+
+        (self.sites, regions)
+    }
+
+    // Drops the growth bookkeeping and keeps only what is needed to answer
+    // lookups, call compute() first for a complete map
+    pub fn finalize(self) -> VoronoiMap<S> {
+        let bounds = *self.grid.bounds();
+        let owners = self.grid.cells().iter().map(|(_, cell)| cell.owner());
+
+        VoronoiMap::new(bounds, owners, self.sites.0.into_iter().map(|wrapper| wrapper.site).collect())
+    }
+}
+
+// Consumes a tesselation cell by cell in row-major order, along with a copy of
+// the owning site
+#[derive(Debug)]
+pub struct IntoCells<S>
+where
+    S: Site
+{
+    bounds: BoundingBox,
+    cells: std::iter::Enumerate<std::vec::IntoIter<Cell>>,
+    sites: SiteTable<S>
+}
+
+impl<S> Iterator for IntoCells<S>
+where
+    S: Site + Clone
+{
+    type Item = (GridIdx, Cell, Option<S>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (position, cell) = self.cells.next()?;
+        let site = cell.owner().map(|owner| self.sites[owner].site.clone());
+
+        Some((self.bounds.idx_at(position), cell, site))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<S, M> IntoIterator for VoronoiTesselation<S, M>
+where
+    S: Site + Clone,
+    M: Metric
+{
+    type Item = (GridIdx, Cell, Option<S>);
+    type IntoIter = IntoCells<S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoCells {
+            bounds: *self.grid.bounds(),
+            cells: self.grid.into_raw().into_vec().into_iter().enumerate(),
+            sites: self.sites
+        }
+    }
+}
+
+impl<S, M> Index<GridIdx> for VoronoiTesselation<S, M>
+where
+    S: Site,
+    M: Metric
+{
+    type Output = Cell;
+
+    fn index(&self, idx: GridIdx) -> &Self::Output {
+        self.get(idx).expect("Coordinates are outside of the tesselation bounds")
+    }
+}
+
+impl<S, M> Index<(isize, isize)> for VoronoiTesselation<S, M>
+where
+    S: Site,
+    M: Metric
+{
+    type Output = Cell;
+
+    fn index(&self, coordinates: (isize, isize)) -> &Self::Output {
+        &self[GridIdx::from(coordinates)]
+    }
+}
+
+impl<S, M> Clone for VoronoiTesselation<S, M>
+where
+    S: Site + Clone,
+    M: Metric
+{
+    fn clone(&self) -> Self {
+        VoronoiTesselation {
+            sites: self.sites.clone(),
+            metric: PhantomData,
+            grid: self.grid.clone(),
+            frontier: self.frontier.clone(),
+            steps: self.steps,
+            report: self.report.clone(),
+            ties: self.ties,
+            counters: self.counters,
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool.clone()
+        }
+    }
+}
+
+// Stands in for the grid when debug printing a tesselation, so that only
+// ownership counts are shown instead of every cell
+struct GridOwnership<'a>(&'a Summary);
+
+impl<'a> fmt::Debug for GridOwnership<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Grid")
+            .field("dimensions", &self.0.bounds.dimensions())
+            .field("region_sizes", &self.0.region_sizes)
+            .field("unclaimed", &self.0.unclaimed)
+            .field("contested", &self.0.contested)
+            .finish()
+    }
+}
+
+impl<S, M> fmt::Debug for VoronoiTesselation<S, M>
+where
+    S: Site + fmt::Debug,
+    M: Metric
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let summary = self.summary();
+
+        f.debug_struct("VoronoiTesselation")
+            .field("bounds", &summary.bounds)
+            .field("sites", &self.owned_sites())
+            .field("steps", &summary.steps)
+            .field("complete", &summary.complete)
+            .field("grid", &GridOwnership(&summary))
+            .finish()
+    }
+}
+
+impl<S, M> fmt::Display for VoronoiTesselation<S, M>
+where
+    S: Site,
+    M: Metric
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.summary().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{AdditiveWeightedEuclidean, Manhattan, MultWeightedEuclidean, PowerEuclidean};
+    use crate::site::WeightedSite;
+
+    #[test]
+    fn build_voronoi_tesselation() {
+        let sites: Vec<(isize, isize, f32)> = vec![
+            (1, 1, 1f32),
+            (2, 2, 2f32),
+            (3, 3, 3f32),
+            (4, 4, 4f32),
+            (5, 5, 5f32),
+            (6, 6, 6f32),
+        ];
+
+        let tess = VoronoiBuilder::new(sites).build().unwrap();
+
+        assert_eq!(tess.len(), 6);
+        assert!(tess.build_report().is_clean());
+    }
+
+    #[test]
+    fn build_voronoi_clip_sites() {
+        let sites: Vec<(isize, isize, f32)> = vec![
+            (1, 1, 1f32),
+            (2, 2, 2f32),
+            (3, 3, 3f32),
+            (4, 4, 4f32),
+            (5, 5, 5f32),
+            (6, 6, 6f32),
+        ];
+
+        let builder = VoronoiBuilder::new(sites).bounds(BoundingBox::new(2, 2, 3, 3));
+
+        let tess = builder.build().unwrap();
+
+        assert_eq!(tess.len(), 3);
+        assert_eq!(tess.build_report().clipped, vec![0, 4, 5]);
+        assert!(!tess.build_report().is_clean());
+    }
+
+    #[test]
+    fn build_voronoi_out_of_bounds_policies() {
+        let sites: Vec<(isize, isize, f32)> = vec![(-3, 1, 1f32), (2, 2, 1f32), (9, 0, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 4, 4);
+
+        let mut clamped = VoronoiBuilder::new(sites.clone())
+            .bounds(bounds)
+            .out_of_bounds(OutOfBoundsPolicy::Clamp)
+            .build()
+            .unwrap();
+        assert_eq!(clamped.len(), 3);
+        assert_eq!(clamped.build_report().clipped, vec![0, 2]);
+        assert_eq!(clamped.owner_at((0, 1)).unwrap().1, &(-3, 1, 1f32));
+        assert_eq!(clamped.owner_at((3, 0)).unwrap().1, &(9, 0, 1f32));
+        clamped.compute();
+        assert_eq!(clamped.summary().unclaimed, 0);
+
+        let expanded = VoronoiBuilder::new(sites.clone())
+            .bounds(bounds)
+            .out_of_bounds(OutOfBoundsPolicy::Expand)
+            .build()
+            .unwrap();
+        assert_eq!(expanded.bounds(), &BoundingBox::new(-3, 0, 13, 4));
+        assert!(expanded.build_report().is_clean());
+
+        let failed = VoronoiBuilder::new(sites).bounds(bounds).out_of_bounds(OutOfBoundsPolicy::Error);
+        assert_eq!(failed.build().err(), Some(VoronoiError::SiteOutOfBounds(0)));
+    }
+
+    #[test]
+    fn compute_discrete_voronoi() {
+        let sites: Vec<(isize, isize, f32)> = vec![
+            (0, 0, 1f32),
+            (1, 1, 1f32),
+            (2, 2, 1f32),
+            (3, 3, 1f32),
+            (4, 4, 1f32),
+            (5, 5, 1f32),
+            (6, 6, 1f32),
+        ];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+
+        tess.compute();
+    }
+
+    #[test]
+    fn compute_large_bounding_box_voronoi() {
+        let sites: Vec<(isize, isize, f32)> = vec![(2, 4, 8f32), (9, 11, 1f32), (4, 9, 8f32), (9, 4, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<MultWeightedEuclidean>()
+            .bounds(BoundingBox::new(0, 0, 14, 14))
+            .build().unwrap();
+
+        tess.compute();
+    }
+
+    #[test]
+    fn build_voronoi_from_iterators() {
+        let tess = VoronoiBuilder::new((0..3).map(|idx| (idx, idx, 1f32)))
+            .add_site((5, 0, 1f32))
+            .extend_sites(vec![(0, 5, 1f32), (0, 0, 2f32)])
+            .build()
+            .unwrap();
+
+        assert_eq!(tess.len(), 5);
+    }
 
     #[test]
-    fn compute_large_bounding_box_voronoi() {
-        let sites: Vec<(isize, isize, f32)> = vec![(2, 4, 8f32), (9, 11, 1f32), (4, 9, 8f32), (9, 4, 1f32)];
+    fn build_voronoi_duplicate_policies() {
+        let sites = vec![(0, 0, 1f32), (3, 3, 1f32), (0, 0, 2f32), (0, 0, 4f32)];
+
+        let kept = VoronoiBuilder::new(sites.clone()).build().unwrap();
+        assert_eq!(kept.len(), 2);
+        assert!(kept.sites_vec().contains(&&(0, 0, 1f32)));
+        assert_eq!(
+            kept.duplicate_sites(),
+            &[DuplicateSite { index: 2, duplicate_of: 0 }, DuplicateSite { index: 3, duplicate_of: 0 }]
+        );
+
+        let merged = VoronoiBuilder::new(sites.clone())
+            .duplicates(DuplicatePolicy::MergeWeights(|a, b| (a.0, a.1, a.2 + b.2)))
+            .build()
+            .unwrap();
+        assert!(merged.sites_vec().contains(&&(0, 0, 7f32)));
+
+        let error = VoronoiBuilder::new(sites.clone()).duplicates(DuplicatePolicy::Error).build();
+        assert_eq!(error.err(), Some(VoronoiError::DuplicateSite(2)));
+
+        let mut allowed = VoronoiBuilder::new(sites)
+            .metric::<AdditiveWeightedEuclidean>()
+            .duplicates(DuplicatePolicy::AllowDuplicates)
+            .build()
+            .unwrap();
+        assert_eq!(allowed.len(), 4);
+        assert!(allowed.duplicate_sites().is_empty());
+
+        allowed.compute();
+        let heaviest = allowed.cells()[0].owner().unwrap();
+        assert_eq!(allowed.owned_sites()[heaviest.0 as usize].1, &(0, 0, 4f32));
+    }
+
+    #[test]
+    fn compute_unweighted_voronoi() {
+        let mut tess = VoronoiBuilder::new(vec![(0isize, 0isize), (4, 4)]).build().unwrap();
+        tess.compute();
+        assert!(tess.is_complete());
+
+        let mut tess = VoronoiBuilder::new(vec![[0isize, 0], [4, 4]]).build().unwrap();
+        tess.compute();
+        assert!(tess.is_complete());
+
+        let mut tess = VoronoiBuilder::new(vec![(0i32, 0i32, 1f32), (-4, 4, 2f32)]).build().unwrap();
+        tess.compute();
+        assert_eq!(tess.owner_at((-4, 3)).unwrap().1, &(-4, 4, 2f32));
+    }
+
+    #[test]
+    fn compute_voronoi_with_generic_weights() {
+        let sites: Vec<(isize, isize, f64)> = vec![(0, 0, 2_000_000.5), (6, 6, 2_000_000.0)];
+        let mut tess = VoronoiBuilder::new(sites).metric::<MultWeightedEuclidean>().build().unwrap();
+        tess.compute();
+        assert!(tess.is_complete());
+
+        let sites: Vec<(isize, isize, u32)> = vec![(0, 0, 3), (6, 6, 1)];
+        let mut tess = VoronoiBuilder::new(sites).metric::<AdditiveWeightedEuclidean>().build().unwrap();
+        tess.compute();
+        assert!(tess.is_complete());
+    }
+
+    #[test]
+    fn compute_voronoi_from_sub_cell_positions() {
+        let sites: Vec<(f64, f64)> = vec![(0.0, 0.0), (4.4, 0.0)];
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        assert_eq!(tess.bounds().dimensions(), (5, 1));
+        assert_eq!(tess.cells()[2].owner(), Some(SiteOwner(0)));
+        assert_eq!(tess.cells()[4].owner(), Some(SiteOwner(1)));
+    }
+
+    #[test]
+    fn map_owners_to_input_order() {
+        let sites: Vec<(isize, isize, f32)> = vec![(5, 5, 1f32), (9, 9, 1f32), (0, 0, 1f32), (5, 5, 2f32)];
+
+        let tess = VoronoiBuilder::new(sites.clone())
+            .bounds(BoundingBox::new(0, 0, 8, 8))
+            .build()
+            .unwrap();
+
+        assert_eq!(tess.original_index(SiteOwner(0)), Some(2));
+        assert_eq!(tess.original_index(SiteOwner(1)), Some(0));
+        assert_eq!(tess.original_index(SiteOwner(2)), None);
+        for (owner, site) in tess.owned_sites() {
+            assert_eq!(&sites[tess.original_index(owner).unwrap()], site);
+        }
+    }
+
+    #[test]
+    fn lookup_site_by_owner() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (4, 4, 2f32)]).build().unwrap();
+        tess.compute();
+
+        let owner = tess.cells()[0].owner().unwrap();
+        assert_eq!(tess.site(owner), Some(&(0, 0, 1f32)));
+        assert_eq!(tess.site(SiteOwner(7)), None);
+
+        let mut owners: Vec<SiteOwner> = tess.owners().collect();
+        owners.sort_unstable_by_key(|owner| owner.0);
+        assert_eq!(owners, vec![SiteOwner(0), SiteOwner(1)]);
+    }
+
+    #[test]
+    fn query_owner_at() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (6, 0, 1f32)])
+            .bounds(BoundingBox::new(0, -1, 7, 3))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        assert_eq!(tess.owner_at((1, -1)), Some((SiteOwner(0), &(0, 0, 1f32))));
+        assert_eq!(tess.owner_at((5, 1)), Some((SiteOwner(1), &(6, 0, 1f32))));
+        assert_eq!(tess.owner_at((7, 0)), None);
+    }
+
+    #[test]
+    fn query_nearest_site() {
+        let tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (6, 0, 1f32)]).build().unwrap();
+
+        assert_eq!(tess.nearest_site(&(1isize, 3isize)), (&(0, 0, 1f32), 10f64.sqrt()));
+        assert_eq!(tess.nearest_site(&(100isize, 0isize)), (&(6, 0, 1f32), 94.0));
+        assert_eq!(tess.nearest_site(&(3isize, 0isize)).0, &(0, 0, 1f32));
+    }
+
+    #[test]
+    fn iterate_region_of_owner() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (5, 0, 1f32)]).build().unwrap();
+        tess.compute();
+
+        let region: Vec<GridIdx> = tess.region_of(SiteOwner(1)).map(|(idx, _)| idx).collect();
+        assert_eq!(region, vec![GridIdx::from((3, 0)), GridIdx::from((4, 0)), GridIdx::from((5, 0))]);
+        assert_eq!(tess.region_of(SiteOwner(2)).count(), 0);
+    }
+
+    #[test]
+    fn fork_partially_computed_voronoi() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (9, 9, 1f32)]).build().unwrap();
+        tess.step();
+
+        let mut fork = tess.clone();
+        fork.compute();
+        assert!(fork.is_complete());
+        assert!(!tess.is_complete());
+        assert_eq!(tess.steps(), 1);
+
+        tess.compute();
+        assert_eq!(tess.summary(), fork.summary());
+    }
+
+    #[test]
+    fn debug_print_voronoi() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (9, 9, 1f32)]).build().unwrap();
+        tess.compute();
+
+        let compact = format!("{:?}", tess);
+        assert!(compact.starts_with("VoronoiTesselation { bounds: BoundingBox"));
+        assert!(compact.contains("dimensions: (10, 10)"));
+        assert!(!compact.contains("Cell"));
+        assert!(format!("{:#?}", tess).lines().count() > 1);
+    }
+
+    #[test]
+    fn swap_metric_and_recompute() {
+        let sites = vec![(0, 0, 1f32), (9, 9, 4f32)];
+
+        let mut tess = VoronoiBuilder::new(sites.clone()).build().unwrap();
+        tess.compute();
+        let euclidean = tess.summary();
+
+        let mut tess = tess.with_metric::<AdditiveWeightedEuclidean>().unwrap();
+        assert_eq!(tess.steps(), 0);
+        tess.compute();
+
+        let mut expected = VoronoiBuilder::new(sites)
+            .metric::<AdditiveWeightedEuclidean>()
+            .build()
+            .unwrap();
+        expected.compute();
+        assert_eq!(tess.summary().region_sizes, expected.summary().region_sizes);
+        assert_ne!(tess.summary().region_sizes, euclidean.region_sizes);
+
+        tess.recompute();
+        assert_eq!(tess.summary().region_sizes, expected.summary().region_sizes);
+
+        // A zero weight is fine without weights but not when dividing by them
+        let tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (5, 5, 0f32)]).build().unwrap();
+        let swapped = tess.with_metric::<MultWeightedEuclidean>();
+        assert_eq!(swapped.err(), Some(VoronoiError::InvalidWeight(1)));
+    }
+
+    #[test]
+    fn expand_bounds_and_continue() {
+        let sites = vec![(1, 1, 1f32), (4, 2, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites.clone())
+            .bounds(BoundingBox::new(0, 0, 6, 4))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let larger = BoundingBox::new(-3, -2, 12, 9);
+        tess.expand_bounds(larger).unwrap();
+        assert_eq!(tess.owner_at((-3, -2)), None);
+        assert_eq!(tess.owner_at((1, 1)).unwrap().0, SiteOwner(0));
+
+        tess.compute();
+        assert!(tess.is_complete());
+        assert_eq!(tess.owner_at((-3, -2)).unwrap().0, SiteOwner(0));
+        assert_eq!(tess.owner_at((8, 6)).unwrap().0, SiteOwner(1));
+
+        let shrunk = tess.expand_bounds(BoundingBox::new(0, 0, 2, 2));
+        assert_eq!(shrunk, Err(VoronoiError::BoundsNotContained));
+    }
+
+    #[test]
+    fn compute_voronoi_from_point_weight_pairs() {
+        let sites = vec![((0isize, 0isize), 1f64), ((6, 6), 3f64)];
+
+        let mut tess = VoronoiBuilder::new(sites).metric::<AdditiveWeightedEuclidean>().build().unwrap();
+        tess.compute();
+
+        assert_eq!(tess.owner_at((3, 3)).unwrap().1, &((6, 6), 3f64));
+    }
+
+    #[test]
+    fn query_site_payloads() {
+        let sites = vec![WeightedSite::new(0, 0, 1f32, "west"), WeightedSite::new(8, 0, 1f32, "east")];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        assert_eq!(tess.owner_at((1, 0)).unwrap().1.data, "west");
+        assert_eq!(tess.nearest_site(&(7isize, 0isize)).0.data, "east");
+
+        let (owner, site) = tess.owner_at((6, 0)).unwrap();
+        assert_eq!(site.data, "east");
+        assert_eq!(tess.region_of(owner).count(), 4);
+    }
+
+    #[test]
+    fn iterate_over_cells() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (3, 0, 2f32)]).build().unwrap();
+        tess.compute();
+
+        let mut count = 0;
+        for (idx, cell, site) in tess {
+            assert_eq!(cell.owner().is_some(), site.is_some());
+            assert_eq!(site.map(|site| site.2), if idx.coordinates().0 < 2 { Some(1f32) } else { Some(2f32) });
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn index_cells_by_coordinates() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (3, 3, 1f32)]).build().unwrap();
+        tess.compute();
+
+        assert_eq!(tess[(0, 1)].owner(), Some(SiteOwner(0)));
+        assert_eq!(tess[GridIdx::from((3, 2))].owner(), Some(SiteOwner(1)));
+        assert!(tess.get((2, 2)).is_some());
+        assert!(tess.get((4, 0)).is_none());
+        assert!(tess.get((-1, 0)).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_outside_bounds_panics() {
+        let tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (3, 3, 1f32)]).build().unwrap();
+
+        let _ = &tess[(-1, 2)];
+    }
+
+    #[test]
+    fn collect_regions_by_site() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0), (5, 0)]).build().unwrap();
+        tess.compute();
+
+        let regions = tess.into_regions();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[&(0, 0)].len(), 3);
+        assert!(regions[&(5, 0)].iter().all(|(idx, _)| idx.coordinates().0 >= 3));
+    }
+
+    #[test]
+    fn split_regions_by_owner() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let sites = vec![
+            WeightedSite::new(0, 0, 1f32, Rc::new(RefCell::new("west"))),
+            WeightedSite::new(3, 0, 1f32, Rc::new(RefCell::new("east"))),
+        ];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        let regions = tess.into_regions_by_owner();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[&SiteOwner(0)].len(), 2);
+        assert!(regions[&SiteOwner(1)].iter().all(|(_, cell)| cell.owner() == Some(SiteOwner(1))));
+    }
+
+    #[test]
+    fn update_weight_and_repair() {
+        let sites = vec![(2, 2, 1f32), (12, 3, 1f32), (6, 12, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 15, 15);
+
+        let mut tess = VoronoiBuilder::new(sites.clone())
+            .metric::<AdditiveWeightedEuclidean>()
+            .bounds(bounds)
+            .build()
+            .unwrap();
+        tess.compute();
+        let before = tess.summary().region_sizes;
+
+        let owner = tess.owner_at((2, 2)).unwrap().0;
+        tess.set_weight(owner, 4f32).unwrap();
+        assert!(tess.is_complete());
+        assert_eq!(tess.site(owner), Some(&(2, 2, 4f32)));
+
+        let after = tess.summary().region_sizes;
+        assert!(after[owner.0 as usize].1 > before[owner.0 as usize].1);
+
+        let mut expected = VoronoiBuilder::new(vec![(2, 2, 4f32), (12, 3, 1f32), (6, 12, 1f32)])
+            .metric::<AdditiveWeightedEuclidean>()
+            .bounds(bounds)
+            .build()
+            .unwrap();
+        expected.compute();
+        assert_eq!(after, expected.summary().region_sizes);
+
+        assert_eq!(tess.set_weight(SiteOwner(9), 1f32), Err(VoronoiError::UnknownSite(SiteOwner(9))));
+        assert_eq!(tess.set_weight(owner, f32::NAN), Err(VoronoiError::NonFiniteWeight(0)));
+    }
+
+    #[test]
+    fn update_weight_with_ties() {
+        // Integer positions and weights under the power distance leave many
+        // cells at exactly the same distance from two sites
+        let sites = vec![(2, 2, 1f32), (8, 2, 1f32), (2, 8, 1f32), (8, 8, 2f32), (5, 5, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 11, 11);
+        let labels = |tess: &VoronoiTesselation<(isize, isize, f32), PowerEuclidean>| {
+            tess.cells().iter().map(|(_, cell)| (cell.owner(), cell.tied_with())).collect::<Vec<_>>()
+        };
+
+        for (site, weight) in [(4, 2f32), (4, 0f32), (0, 2f32), (3, 1f32), (3, 3f32), (1, 0f32)] {
+            let mut tess = VoronoiBuilder::new(sites.clone())
+                .metric::<PowerEuclidean>()
+                .ties(TieBreak::Unowned)
+                .bounds(bounds)
+                .build()
+                .unwrap();
+            tess.compute();
+            assert!(tess.cells().iter().any(|(_, cell)| cell.tied_with().is_some()));
+            let owner = tess.owner_at(sites[site].coordinates()).unwrap().0;
+            tess.set_weight(owner, weight).unwrap();
+
+            let mut changed = sites.clone();
+            changed[site].2 = weight;
+            let mut expected = VoronoiBuilder::new(changed)
+                .metric::<PowerEuclidean>()
+                .ties(TieBreak::Unowned)
+                .bounds(bounds)
+                .build()
+                .unwrap();
+            expected.compute();
+            assert_eq!(labels(&tess), labels(&expected), "site {} weight {}", site, weight);
+        }
+    }
+
+    #[test]
+    fn compute_is_deterministic() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (4, 0, 1f32), (2, 4, 1f32), (6, 5, 1f32)];
+
+        let owners = |sites: Vec<(isize, isize, f32)>| {
+            let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+            tess.compute();
+            tess.cells().iter().map(|(_, cell)| cell.owner()).collect::<Vec<_>>()
+        };
+
+        let first = owners(sites.clone());
+        for _ in 0..8 {
+            assert_eq!(owners(sites.clone()), first);
+        }
+    }
+
+    #[test]
+    fn build_voronoi_weight_policies() {
+        let sites = vec![(0, 0, 2f32), (4, 0, -1f32), (0, 4, 0f32)];
+
+        let skipped = VoronoiBuilder::new(sites.clone())
+            .metric::<MultWeightedEuclidean>()
+            .weights(WeightPolicy::Skip)
+            .build()
+            .unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped.build_report().invalid_weights, vec![1, 2]);
+
+        let clamped = VoronoiBuilder::new(sites.clone())
+            .metric::<MultWeightedEuclidean>()
+            .weights(WeightPolicy::Clamp(|site| (site.0, site.1, site.2.max(0.5))))
+            .build()
+            .unwrap();
+        assert_eq!(clamped.len(), 3);
+        assert!(clamped.sites().all(|site| site.2 > 0.0));
+
+        let allowed = VoronoiBuilder::new(sites.clone())
+            .metric::<MultWeightedEuclidean>()
+            .weights(WeightPolicy::Allow)
+            .build()
+            .unwrap();
+        assert!(allowed.build_report().is_clean());
+
+        let additive = VoronoiBuilder::new(sites).metric::<AdditiveWeightedEuclidean>().build();
+        assert!(additive.is_ok());
+    }
+
+    #[test]
+    fn settle_simultaneous_claims() {
+        let sites = vec![(0, 0, 1f32), (2, 0, 1f32), (5, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites.clone()).ties(TieBreak::Unowned).build().unwrap();
+        tess.step();
 
+        assert_eq!(tess.owner_at((1, 0)), None);
+        assert!(tess[(1, 0)].contested());
+        assert_eq!(tess.owner_at((3, 0)).unwrap().0, SiteOwner(1));
+        assert_eq!(tess.owner_at((4, 0)).unwrap().0, SiteOwner(2));
+
+        tess.compute();
+        assert_eq!(tess.summary().region_sizes, vec![(SiteOwner(0), 1), (SiteOwner(1), 2), (SiteOwner(2), 2)]);
+
+        // By default the tie goes to the site given first
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        assert_eq!(tess.tie_break(), TieBreak::FirstSite);
+        tess.compute();
+
+        assert_eq!(tess.owner_at((1, 0)).unwrap().0, SiteOwner(0));
+        assert!(tess[(1, 0)].contested());
+        assert_eq!(tess.summary().unclaimed, 0);
+    }
+
+    #[test]
+    fn diagnose_metric_problems() {
+        let sites = vec![(0, 0, 1f32), (6, 0, 1f32), (3, 6, 1f32)];
+        let tess = VoronoiBuilder::new(sites.clone()).metric::<MultWeightedEuclidean>().build().unwrap();
+        assert!(tess.diagnose().is_empty());
+
+        let sites = vec![(0, 0, 1f32), (9, 0, 1f32), (1, 0, 8f32), (5, 8, 4f32)];
+        let mut tess = VoronoiBuilder::new(sites.clone()).metric::<MultWeightedEuclidean>().build().unwrap();
+        let split = |first, second| MetricWarning::DisconnectedRegions(SiteOwner(first), SiteOwner(second));
+        assert_eq!(tess.diagnose(), vec![split(0, 2), split(1, 2), split(1, 3), split(2, 3)]);
+
+        tess.compute_exact();
+        for (idx, cell) in tess.cells() {
+            let (nearest, _) = tess.nearest_site(&idx);
+            assert_eq!(tess.site(cell.owner().unwrap()), Some(nearest));
+        }
+
+        let checked = VoronoiBuilder::new(sites).metric::<MultWeightedEuclidean>().check_metric(true).build();
+        assert_eq!(checked.err(), Some(VoronoiError::UnreliableMetric));
+
+        // Nearly equal weights give borders that are straight across the grid
+        let sites = vec![(0, 0, 1f32), (6, 0, 1.0001f32), (3, 6, 1f32)];
+        let tess = VoronoiBuilder::new(sites).metric::<MultWeightedEuclidean>().build().unwrap();
+        assert!(tess.diagnose().is_empty());
+
+        // The circle around the lighter site cuts across the bounds, leaving
+        // a piece of the heavier site's region on the far side
+        let sites = vec![(15, 3, 1f32), (20, 3, 1.5f32)];
         let mut tess = VoronoiBuilder::new(sites)
             .metric::<MultWeightedEuclidean>()
-            .bounds(BoundingBox::new(0, 0, 14, 14))
-            .build();
+            .bounds(BoundingBox::new(0, 0, 31, 7))
+            .build()
+            .unwrap();
+        assert_eq!(tess.diagnose(), vec![split(0, 1)]);
+        tess.compute();
+        assert!(!tess.verify(1, 1).is_exact());
+
+        // The large power circle of the second site covers the first site
+        let tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (2, 0, 5f32)]).metric::<PowerEuclidean>().build().unwrap();
+        assert_eq!(tess.diagnose(), vec![MetricWarning::SiteOutsideRegion(SiteOwner(0))]);
+    }
+
+    #[test]
+    fn verify_against_nearest_sites() {
+        let sites = vec![(0, 0, 1f32), (7, 1, 1f32), (3, 6, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites.clone()).build().unwrap();
+        tess.compute();
+
+        let verification = tess.verify(1, 5);
+        assert_eq!(verification.checked, tess.cells().len());
+        assert!(verification.is_exact());
+        assert_eq!(tess.verify(4, 5).checked, tess.cells().len().div_ceil(4));
+
+        // Growth stops at the tied cells and leaves the far corners out
+        let mut tess = VoronoiBuilder::new(sites).ties(TieBreak::Unowned).build().unwrap();
+        tess.step();
+        let verification = tess.verify(1, 2);
+        assert!(!verification.is_exact());
+        assert_eq!(verification.worst.len(), 2);
+        assert_eq!(verification.worst[0].owner, None);
+        assert_eq!(verification.worst[0].excess, f64::INFINITY);
+    }
+
+    #[test]
+    fn claim_tied_cell_later() {
+        let sites = vec![(0, 0, 1f32), (2, 0, 1f32), (1, 3, 10f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<MultWeightedEuclidean>()
+            .ties(TieBreak::Unowned)
+            .build()
+            .unwrap();
+        tess.step();
+
+        assert_eq!(tess.owner_at((1, 0)), None);
+        assert!(tess[(1, 0)].contested());
+
+        // The heavy site reaches the tied cell two steps later and is closer
+        tess.compute();
+        assert_eq!(tess.owner_at((1, 0)).unwrap().1, &(1, 3, 10f32));
+        assert_eq!(tess.claim_step((1, 0)), Some(3));
+        assert!(tess[(1, 0)].contested());
+    }
 
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn compute_in_thread_pool() {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        let sites = vec![(0, 0, 1f32), (9, 3, 1f32), (4, 8, 1f32)];
+
+        let mut pooled = VoronoiBuilder::new(sites.clone()).thread_pool(Arc::new(pool)).build().unwrap();
+        pooled.compute();
+
+        let mut global = VoronoiBuilder::new(sites).build().unwrap();
+        global.compute();
+        assert_eq!(pooled.summary(), global.summary());
+    }
+
+    #[test]
+    fn write_buffers_in_place() {
+        let sites = vec![(0, 0, 1f32), (3, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 4, 2)).build().unwrap();
+        tess.compute();
+
+        let label = |_: GridIdx, _: &Cell, site: Option<&(isize, isize, f32)>| {
+            site.map_or(0u8, |site| site.0 as u8 + 1)
+        };
+
+        let mut visited = Vec::new();
+        tess.for_each_cell(|idx, cell, site| visited.push(label(idx, cell, site)));
+
+        let mut buffer = [0u8; 8];
+        tess.write_buffer_into(&mut buffer, label).unwrap();
+        assert_eq!(&buffer[..], &visited[..]);
+        assert_eq!(
+            tess.write_buffer_into(&mut [0u8; 3], label),
+            Err(VoronoiError::BufferLength { expected: 8, actual: 3 })
+        );
+
+        let mut written = Vec::new();
+        tess.write_buffer(&mut written, |idx, cell, site| [label(idx, cell, site)]).unwrap();
+        assert_eq!(written, visited);
+
+        assert_eq!(tess.into_buffer(label), visited);
+    }
+
+    #[test]
+    fn count_compute_work() {
+        let sites = vec![(0, 0, 1f32), (4, 0, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 5, 1);
+
+        let mut plain = VoronoiBuilder::new(sites.clone()).bounds(bounds).build().unwrap();
+        plain.compute();
+        assert_eq!(plain.counters(), None);
+
+        let mut tess = VoronoiBuilder::new(sites)
+            .bounds(bounds)
+            .ties(TieBreak::Unowned)
+            .instrument(true)
+            .build()
+            .unwrap();
+        tess.compute();
+
+        // Both sites reach the middle cell in the second step and tie on it
+        let counters = *tess.counters().unwrap();
+        assert_eq!(counters.steps, tess.steps());
+        assert_eq!(counters.cells_claimed, 2);
+        assert_eq!(counters.claims, 4);
+        assert_eq!(counters.conflicts_resolved, 1);
+        assert_eq!(counters.ties, 1);
+        assert_eq!(counters.cells_scanned, 6);
+
+        tess.recompute();
+        let recomputed = tess.counters().unwrap();
+        assert_eq!((recomputed.steps, recomputed.cells_claimed), (counters.steps, counters.cells_claimed));
+    }
+
+    #[test]
+    fn merge_small_regions_into_neighbors() {
+        // The middle region has 4 cells, the left one 7 and the right one 5
+        let build = || {
+            let sites = vec![(3, 0, 1f32), (9, 0, 1f32), (11, 0, 1f32)];
+            VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 16, 1)).build().unwrap()
+        };
+
+        let mut tess = build();
+        let merged = tess.merge_small_regions(5, MergeTarget::Largest);
+        assert_eq!(merged, vec![MergedRegion { index: 1, merged_into: 0 }]);
+        assert_eq!(tess.len(), 2);
+        assert_eq!(tess.owner_at((9, 0)), Some((SiteOwner(0), &(3, 0, 1f32))));
+        assert_eq!(tess.region_of(SiteOwner(1)).count(), 5);
+        assert_eq!(tess.original_index(SiteOwner(1)), Some(2));
+
+        let mut tess = build();
+        let merged = tess.merge_small_regions(5, MergeTarget::Nearest);
+        assert_eq!(merged, vec![MergedRegion { index: 1, merged_into: 2 }]);
+        assert_eq!(tess.owner_at((9, 0)), Some((SiteOwner(1), &(11, 0, 1f32))));
+        assert_eq!(tess.region_of(SiteOwner(1)).count(), 9);
+
+        // The right region is merged too once it is below the minimum
+        let mut tess = build();
+        let merged = tess.merge_small_regions(8, MergeTarget::Largest);
+        assert_eq!(merged, vec![MergedRegion { index: 1, merged_into: 0 }, MergedRegion { index: 2, merged_into: 0 }]);
+        assert_eq!(tess.len(), 1);
+        assert_eq!(tess.region_of(SiteOwner(0)).count(), 16);
+
+        // A lone region has nothing to merge into
+        assert!(tess.merge_small_regions(100, MergeTarget::Largest).is_empty());
+    }
+
+    #[test]
+    fn smooth_borders_with_majority_filter() {
+        let sites = vec![(0, 2, 1f32), (6, 2, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 7, 5))
+            .build()
+            .unwrap();
+        tess.compute();
+        let expected: Vec<Option<SiteOwner>> = tess.cells().iter().map(|(_, cell)| cell.owner()).collect();
+
+        // A single cell sticking out of the left region into the right one
+        tess.grid[GridIdx::from((4, 2))].restore_owner(SiteOwner(0));
+        assert_eq!(tess.majority_filter(3), 1);
+        assert_eq!(tess.cells().iter().map(|(_, cell)| cell.owner()).collect::<Vec<_>>(), expected);
+        assert_eq!(tess.majority_filter(3), 0);
+
+        // Seeds keep their owner even when surrounded
+        for idx in BoundingBox::new(0, 0, 7, 5).coordinates_iter() {
+            if idx != GridIdx::from((6, 2)) {
+                tess.grid[idx].restore_owner(SiteOwner(0));
+            }
+        }
+        assert_eq!(tess.majority_filter(1), 0);
+        assert_eq!(tess.region_of(SiteOwner(1)).count(), 1);
+    }
+
+    #[test]
+    fn supersample_border_cells() {
+        // The center of (2, 0) is as far from both sites, but most of the cell
+        // is closer to the second one
+        let sites = vec![(0.0, 0.0), (3.0, 1.0)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 6, 4))
+            .build()
+            .unwrap();
         tess.compute();
+        assert_eq!(tess[(2, 0)].owner(), Some(SiteOwner(0)));
+        let before: Vec<Option<SiteOwner>> = tess.cells().iter().map(|(_, cell)| cell.owner()).collect();
+
+        let histograms = tess.supersample(4);
+        assert_eq!(histograms[2], vec![(SiteOwner(0), 7), (SiteOwner(1), 9)]);
+        assert_eq!(tess[(2, 0)].owner(), Some(SiteOwner(1)));
+        assert_eq!(histograms[0], vec![(SiteOwner(0), 16)]);
+
+        let changed = tess.cells().iter().zip(&before).filter(|((_, cell), &owner)| cell.owner() != owner).count();
+        assert_eq!(changed, 1);
+    }
+
+    #[test]
+    fn build_voronoi_errors() {
+        let empty: Vec<(isize, isize, f32)> = Vec::new();
+        assert_eq!(VoronoiBuilder::new(empty).build().err(), Some(VoronoiError::NoSites));
+
+        let clipped = VoronoiBuilder::new(vec![(5, 5, 1f32)]).bounds(BoundingBox::new(0, 0, 2, 2));
+        assert_eq!(clipped.build().err(), Some(VoronoiError::NoSitesInBounds));
+
+        let flat = VoronoiBuilder::new(vec![(0, 0, 1f32)]).bounds(BoundingBox::new(0, 0, 0, 2));
+        assert_eq!(flat.build().err(), Some(VoronoiError::ZeroAreaBounds));
+
+        let infinite = VoronoiBuilder::new(vec![(0, 0, 1f32), (1, 1, f32::INFINITY)]);
+        assert_eq!(infinite.build().err(), Some(VoronoiError::NonFiniteWeight(1)));
+
+        let zero = VoronoiBuilder::new(vec![(0, 0, 1f32), (1, 1, 0f32)]).metric::<MultWeightedEuclidean>();
+        assert_eq!(zero.build().err(), Some(VoronoiError::InvalidWeight(1)));
+
+        let spread = VoronoiBuilder::new(vec![(isize::MIN, 0, 1f32), (isize::MAX, 0, 1f32)]);
+        assert_eq!(spread.build().err(), Some(VoronoiError::BoundsOverflow));
+
+        let huge = VoronoiBuilder::new(vec![(0, 0, 1f32)]).bounds(BoundingBox::new(0, 0, usize::MAX, 2));
+        assert_eq!(huge.build().err(), Some(VoronoiError::BoundsOverflow));
+
+        let wide = VoronoiBuilder::new(vec![[0usize, 0, 1], [usize::MAX, 0, 1]]);
+        assert_eq!(wide.build().err(), Some(VoronoiError::CoordinatesOutOfRange(1)));
+
+        let nan = VoronoiBuilder::new(vec![(0.5, 0.5), (f64::NAN, 1.0), (2.0, f64::INFINITY)]);
+        assert_eq!(nan.build().err(), Some(VoronoiError::NonFinitePosition(1)));
+
+        let far = VoronoiBuilder::new(vec![(0.5, 0.5), (1e300, 1.0)]);
+        assert_eq!(far.build().err(), Some(VoronoiError::CoordinatesOutOfRange(1)));
+
+        // Too many sites to build in a test, the limit is checked on its own
+        assert_eq!(check_site_count(MAX_SITES - 1), Ok(()));
+        assert_eq!(check_site_count(MAX_SITES), Err(VoronoiError::TooManySites));
     }
 
+    #[test]
+    fn summarize_computed_voronoi() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (3, 3, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        assert!(!tess.summary().complete);
+
+        tess.compute();
+        let summary = tess.summary();
+
+        assert!(summary.complete);
+        assert_eq!(summary.sites, 2);
+        let owned: usize = summary.region_sizes.iter().map(|&(_, size)| size).sum();
+        assert_eq!(owned + summary.unclaimed, 16);
+        assert!(tess.to_string().starts_with("bounds: 4x4 at (0, 0)"));
+    }
 }
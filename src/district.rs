@@ -0,0 +1,208 @@
+use crate::discrete_voronoi::{DuplicatePolicy, VoronoiBuilder};
+use crate::error::VoronoiError;
+use crate::grid::BoundingBox;
+use crate::metric::AdditiveWeightedEuclidean;
+use crate::site::Point;
+
+use std::f64::consts::PI;
+
+// Settings for balancing district populations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistrictParams {
+    // Largest allowed difference from the mean population, as a fraction of it
+    pub tolerance: f64,
+    pub max_iterations: usize,
+    // How far weights move per round, as a fraction of the typical district
+    // radius for a district that is off by its whole target population
+    pub step: f64
+}
+
+impl Default for DistrictParams {
+    fn default() -> Self {
+        DistrictParams {
+            tolerance: 0.05,
+            max_iterations: 100,
+            step: 0.1
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct District {
+    // Final position and additive weight of the district site
+    pub site: (f64, f64),
+    pub weight: f64,
+    pub population: f64,
+    // Number of cells in the district
+    pub area: usize,
+    // Polsby-Popper score 4 pi area / perimeter^2 over cell edges, about 0.785
+    // for a square and lower for stretched out shapes
+    pub compactness: f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Districting {
+    // Districts in the order of the given sites
+    pub districts: Vec<District>,
+    // District of every cell in row-major order
+    pub labels: Vec<Option<usize>>,
+    pub iterations: usize,
+    // Whether every district ended up within the tolerance
+    pub balanced: bool
+}
+
+// Splits the bounds into one district per site so that the populations,
+// given per cell in row-major order, are about equal. Every round moves the
+// sites to the population weighted centroid of their district and grows the
+// additive weight of districts below the mean population while shrinking
+// the others, until all of them are within the tolerance.
+pub fn balance_districts(
+    population: &[f64],
+    bounds: BoundingBox,
+    sites: &[(f64, f64)],
+    params: &DistrictParams
+) -> Result<Districting, VoronoiError> {
+    bounds.validate()?;
+    let (width, height) = bounds.dimensions();
+    if population.len() != width * height {
+        return Err(VoronoiError::BufferLength {
+            expected: width * height,
+            actual: population.len()
+        });
+    }
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+
+    let target = population.iter().sum::<f64>() / sites.len() as f64;
+    let radius = ((width * height) as f64 / sites.len() as f64).sqrt();
+    let mut districts: Vec<(f64, f64, f64)> = sites.iter().map(|&(x, y)| (x, y, 0.0)).collect();
+
+    let mut iterations = 0;
+    loop {
+        let labels = assign(&districts, bounds)?;
+        let summaries = summarize(&labels, population, bounds, districts.len());
+        let within = |summary: &Summary| (summary.population - target).abs() <= params.tolerance * target;
+        let balanced = target <= 0.0 || summaries.iter().all(within);
+
+        if balanced || iterations >= params.max_iterations {
+            let districts = districts
+                .iter()
+                .zip(summaries)
+                .map(|(&(x, y, weight), summary)| District {
+                    site: (x, y),
+                    weight,
+                    population: summary.population,
+                    area: summary.area,
+                    compactness: match summary.perimeter {
+                        0 => 0.0,
+                        perimeter => 4.0 * PI * summary.area as f64 / (perimeter as f64).powi(2)
+                    }
+                })
+                .collect();
+
+            return Ok(Districting {
+                districts,
+                labels,
+                iterations,
+                balanced
+            });
+        }
+
+        for (district, summary) in districts.iter_mut().zip(&summaries) {
+            if let Some(centroid) = summary.centroid {
+                district.0 = centroid.0;
+                district.1 = centroid.1;
+            }
+            district.2 += params.step * radius * (target - summary.population) / target;
+        }
+        iterations += 1;
+    }
+}
+
+fn assign(districts: &[(f64, f64, f64)], bounds: BoundingBox) -> Result<Vec<Option<usize>>, VoronoiError> {
+    let mut tesselation = VoronoiBuilder::new(districts.iter().copied())
+        .metric::<AdditiveWeightedEuclidean>()
+        .bounds(bounds)
+        .duplicates(DuplicatePolicy::AllowDuplicates)
+        .build()?;
+    tesselation.compute();
+
+    Ok(tesselation
+        .cells()
+        .iter()
+        .map(|(_, cell)| cell.owner().and_then(|owner| tesselation.original_index(owner)))
+        .collect())
+}
+
+struct Summary {
+    population: f64,
+    area: usize,
+    perimeter: usize,
+    centroid: Option<(f64, f64)>
+}
+
+fn summarize(labels: &[Option<usize>], population: &[f64], bounds: BoundingBox, districts: usize) -> Vec<Summary> {
+    let (width, _) = bounds.dimensions();
+    let mut sums = vec![(0.0, 0.0, 0.0, 0, 0); districts];
+
+    for ((position, idx), &label) in bounds.coordinates_iter().enumerate().zip(labels) {
+        let label = match label {
+            Some(label) => label,
+            None => continue
+        };
+        let (x, y) = idx.coordinates();
+        let people = population[position];
+        let sum = &mut sums[label];
+
+        sum.0 += people;
+        sum.1 += x as f64 * people;
+        sum.2 += y as f64 * people;
+        sum.3 += 1;
+
+        // Edges facing another district or the outside of the bounds
+        let inside_neighbors: Vec<usize> = idx
+            .neighbors(&bounds)
+            .map(|neighbor| bounds.translate_idx(neighbor))
+            .map(|(x, y)| x + y * width)
+            .collect();
+        sum.4 += 4 - inside_neighbors.len();
+        sum.4 += inside_neighbors.iter().filter(|&&neighbor| labels[neighbor] != Some(label)).count();
+    }
+
+    sums.into_iter()
+        .map(|(people, x, y, area, perimeter)| Summary {
+            population: people,
+            area,
+            perimeter,
+            centroid: if people > 0.0 { Some((x / people, y / people)) } else { None }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balance_crowded_side() {
+        // People get denser towards the right
+        let bounds = BoundingBox::new(0, 0, 16, 16);
+        let population: Vec<f64> = bounds.coordinates_iter().map(|idx| 1.0 + idx.coordinates().0 as f64).collect();
+        let sites = [(4.0, 4.0), (12.0, 4.0), (4.0, 12.0), (12.0, 12.0)];
+        let params = DistrictParams {
+            tolerance: 0.1,
+            ..DistrictParams::default()
+        };
+
+        let result = balance_districts(&population, bounds, &sites, &params).unwrap();
+        assert!(result.balanced);
+        let target = population.iter().sum::<f64>() / 4.0;
+        for district in &result.districts {
+            assert!((district.population - target).abs() <= 0.1 * target);
+            assert!(district.compactness > 0.0 && district.compactness < 1.0);
+        }
+        assert_eq!(result.districts.iter().map(|district| district.area).sum::<usize>(), 256);
+        assert!(result.labels.iter().all(|label| label.is_some()));
+    }
+}
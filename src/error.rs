@@ -0,0 +1,80 @@
+use crate::discrete_voronoi::SiteOwner;
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoronoiError {
+    // The builder was given no sites at all
+    NoSites,
+    // The bounds have a width or height of zero
+    ZeroAreaBounds,
+    // Every site was clipped by the bounds
+    NoSitesInBounds,
+    // The site at the given index of the input has an infinite or NaN weight
+    NonFiniteWeight(usize),
+    // The site at the given index of the input has a weight the metric cannot
+    // use
+    InvalidWeight(usize),
+    // The site at the given index of the builder input has no seed cell inside
+    // of the bounds
+    SiteOutOfBounds(usize),
+    // The site at the given index of the builder input was placed on the same
+    // cell as an earlier site
+    DuplicateSite(usize),
+    // New bounds for a tesselation do not contain the current bounds
+    BoundsNotContained,
+    // No site in the tesselation has the given owner id
+    UnknownSite(SiteOwner),
+    // A buffer given for the cells does not have one element per cell
+    BufferLength { expected: usize, actual: usize },
+    // The bounds needed for the sites hold more cells than can be addressed
+    BoundsOverflow,
+    // The metric checks found that growing regions would give a wrong result
+    UnreliableMetric,
+    // A time step for a simulation is not a positive, finite number
+    InvalidTimeStep,
+    // More sites than a cell can tell apart by owner id
+    TooManySites,
+    // The site at the given index of the builder input has coordinates that do
+    // not fit in isize
+    CoordinatesOutOfRange(usize),
+    // The site at the given index of the builder input has an infinite or NaN
+    // position
+    NonFinitePosition(usize),
+    // The site at the given index of the input was put in a team that is not
+    // below the number of sites
+    InvalidTeam(usize)
+}
+
+impl fmt::Display for VoronoiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VoronoiError::NoSites => write!(f, "no sites were given"),
+            VoronoiError::ZeroAreaBounds => write!(f, "bounds must have a non-zero width and height"),
+            VoronoiError::NoSitesInBounds => write!(f, "all sites are outside of the bounds"),
+            VoronoiError::NonFiniteWeight(index) => write!(f, "site {} has a non-finite weight", index),
+            VoronoiError::InvalidWeight(index) => write!(f, "site {} has a weight the metric cannot use", index),
+            VoronoiError::SiteOutOfBounds(index) => write!(f, "site {} is outside of the bounds", index),
+            VoronoiError::DuplicateSite(index) => write!(f, "site {} is on the same cell as an earlier site", index),
+            VoronoiError::BoundsNotContained => write!(f, "new bounds must contain the current bounds"),
+            VoronoiError::UnknownSite(owner) => write!(f, "no site with owner id {}", owner.0),
+            VoronoiError::BufferLength { expected, actual } => {
+                write!(f, "buffer holds {} elements but the grid has {} cells", actual, expected)
+            },
+            VoronoiError::BoundsOverflow => write!(f, "bounds are too large to address every cell"),
+            VoronoiError::UnreliableMetric => {
+                write!(f, "the metric and weights break the assumptions of region growing")
+            },
+            VoronoiError::InvalidTimeStep => write!(f, "time steps must be positive and finite"),
+            VoronoiError::TooManySites => write!(f, "more sites than owner ids can address"),
+            VoronoiError::CoordinatesOutOfRange(index) => {
+                write!(f, "site {} has coordinates that do not fit in isize", index)
+            },
+            VoronoiError::NonFinitePosition(index) => write!(f, "site {} has a non-finite position", index),
+            VoronoiError::InvalidTeam(index) => write!(f, "site {} is in a team past the number of sites", index)
+        }
+    }
+}
+
+impl Error for VoronoiError {}
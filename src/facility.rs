@@ -0,0 +1,227 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::grid::GridIdx;
+use crate::metric::Metric;
+use crate::site::Site;
+
+// An empty circle centered on a cell, reaching to the closest site
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmptyCircle {
+    pub center: GridIdx,
+    pub radius: f64
+}
+
+// Distance from every cell to its owner in row-major order, None for cells
+// without an owner
+fn owner_distances<S, M>(tesselation: &VoronoiTesselation<S, M>) -> impl Iterator<Item = Option<f64>> + '_
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    tesselation.cells().iter().map(move |(idx, cell)| {
+        let site = tesselation.site(cell.owner()?)?;
+        Some(M::distance(site, &idx).into())
+    })
+}
+
+// The cell furthest from its closest site, the best spot for a new site, and
+// that distance as the radius. Only cells set in the row-major mask are
+// considered if one is given, and ties go to the first cell in row-major
+// order. Expects a computed tesselation, None if no cell qualifies.
+pub fn largest_empty_circle<S, M>(tesselation: &VoronoiTesselation<S, M>, mask: Option<&[bool]>) -> Option<EmptyCircle>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let mut largest: Option<EmptyCircle> = None;
+
+    for (position, ((idx, _), distance)) in tesselation.cells().iter().zip(owner_distances(tesselation)).enumerate() {
+        let allowed = mask.is_none_or(|mask| mask.get(position).copied().unwrap_or(false));
+        let radius = match distance {
+            Some(radius) if allowed => radius,
+            _ => continue
+        };
+
+        if largest.is_none_or(|largest| radius > largest.radius) {
+            largest = Some(EmptyCircle {
+                center: idx,
+                radius
+            });
+        }
+    }
+
+    largest
+}
+
+// How a site serves the cells of its region
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteCoverage {
+    pub owner: SiteOwner,
+    // Number of cells in the region
+    pub cells: usize,
+    // Mean and max distance from the site to its cells, 0 for empty regions
+    pub mean_distance: f64,
+    pub max_distance: f64,
+    // Number of cells per distance band of the requested width starting at 0,
+    // negative distances fall into the first band
+    pub histogram: Vec<usize>
+}
+
+// Coverage of every site of a computed tesselation, sorted by owner id
+pub fn coverage_report<S, M>(tesselation: &VoronoiTesselation<S, M>, bin_width: f64) -> Vec<SiteCoverage>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let mut report: Vec<SiteCoverage> = tesselation
+        .owners()
+        .map(|owner| SiteCoverage {
+            owner,
+            cells: 0,
+            mean_distance: 0.0,
+            max_distance: 0.0,
+            histogram: Vec::new()
+        })
+        .collect();
+
+    for ((_, cell), distance) in tesselation.cells().iter().zip(owner_distances(tesselation)) {
+        let (owner, distance) = match (cell.owner(), distance) {
+            (Some(owner), Some(distance)) => (owner, distance),
+            _ => continue
+        };
+        let coverage = &mut report[owner.0 as usize];
+
+        coverage.max_distance = if coverage.cells == 0 { distance } else { coverage.max_distance.max(distance) };
+        coverage.cells += 1;
+        coverage.mean_distance += distance;
+
+        let bin = (distance / bin_width).floor().max(0.0) as usize;
+        if coverage.histogram.len() <= bin {
+            coverage.histogram.resize(bin + 1, 0);
+        }
+        coverage.histogram[bin] += 1;
+    }
+
+    for coverage in &mut report {
+        if coverage.cells > 0 {
+            coverage.mean_distance /= coverage.cells as f64;
+        }
+    }
+
+    report
+}
+
+// Cells out of reach of every site for a maximum service distance
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceGaps {
+    pub radius: f64,
+    // Whether every cell is within the radius of its owner, row-major
+    pub covered: Vec<bool>,
+    // Coordinates of the cells not covered, in row-major order
+    pub uncovered: Vec<GridIdx>,
+    // Number of cells each site covers within the radius, sorted by id
+    pub covered_by_site: Vec<(SiteOwner, usize)>
+}
+
+impl ServiceGaps {
+    // Number of cells not covered by any site
+    pub fn uncovered_area(&self) -> usize {
+        self.uncovered.len()
+    }
+}
+
+// Finds the cells further than the radius from their owner, which in a
+// computed tesselation is the closest site, so no site reaches them.
+// Ownership is left untouched, this only adds a coverage layer on top.
+pub fn service_gaps<S, M>(tesselation: &VoronoiTesselation<S, M>, radius: f64) -> ServiceGaps
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let mut gaps = ServiceGaps {
+        radius,
+        covered: Vec::with_capacity(tesselation.cells().len()),
+        uncovered: Vec::new(),
+        covered_by_site: tesselation.owners().map(|owner| (owner, 0)).collect()
+    };
+
+    for ((idx, cell), distance) in tesselation.cells().iter().zip(owner_distances(tesselation)) {
+        let covered = distance.is_some_and(|distance| distance <= radius);
+        gaps.covered.push(covered);
+
+        match cell.owner() {
+            Some(owner) if covered => gaps.covered_by_site[owner.0 as usize].1 += 1,
+            _ if !covered => gaps.uncovered.push(idx),
+            _ => {}
+        }
+    }
+
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn find_largest_empty_circle() {
+        let sites = vec![(0, 0, 1f32), (0, 4, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 6, 5))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let circle = largest_empty_circle(&tess, None).unwrap();
+        assert_eq!(circle, EmptyCircle { center: GridIdx::from((5, 2)), radius: 7.0 });
+
+        // Only the left column is allowed
+        let mask: Vec<bool> = tess.cells().iter().map(|(idx, _)| idx < GridIdx::from((1, 0))).collect();
+        let circle = largest_empty_circle(&tess, Some(&mask)).unwrap();
+        assert_eq!(circle, EmptyCircle { center: GridIdx::from((0, 2)), radius: 2.0 });
+    }
+
+    #[test]
+    fn report_site_coverage() {
+        let sites = vec![(0, 0, 1f32), (5, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 7, 1))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let report = coverage_report(&tess, 2.0);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].cells, 3);
+        assert_eq!(report[0].mean_distance, 1.0);
+        assert_eq!(report[0].max_distance, 2.0);
+        assert_eq!(report[0].histogram, vec![2, 1]);
+        assert_eq!(report[1].cells, 4);
+        assert_eq!(report[1].histogram, vec![3, 1]);
+    }
+
+    #[test]
+    fn find_service_gaps() {
+        let sites = vec![(0, 0, 1f32), (8, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 9, 1))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let gaps = service_gaps(&tess, 2.0);
+        assert_eq!(gaps.uncovered_area(), 3);
+        assert_eq!(gaps.uncovered, vec![GridIdx::from((3, 0)), GridIdx::from((4, 0)), GridIdx::from((5, 0))]);
+        assert_eq!(gaps.covered_by_site, vec![(SiteOwner(0), 3), (SiteOwner(1), 3)]);
+        assert_eq!(gaps.covered.iter().filter(|&&covered| covered).count(), 6);
+    }
+}
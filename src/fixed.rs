@@ -0,0 +1,191 @@
+use crate::discrete_voronoi::{check_site_count, resolve_claims, Resolution, SiteOwner, TieBreak};
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, Cell, Cells, GridIdx, NO_STEP};
+use crate::metric::Metric;
+use crate::site::Site;
+
+use std::ops::Index;
+
+// Exact brute-force solver for small grids with a size fixed at compile time.
+// It is not a backend of VoronoiTesselation: there is no region growing, no
+// stepping and no updates of single sites, only solve(), which measures every
+// cell against every site with the same claim rules as the tesselation. That
+// costs one distance per cell and site, fine for the handful of sites on a
+// small grid updated every frame, where the cells stored inline keep solving
+// off the heap. Owners are the positions of the sites in the slice given to
+// solve(), and the cells are read through the same Cells view as a
+// tesselation's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedGrid<const W: usize, const H: usize> {
+    bounds: BoundingBox,
+    ties: TieBreak,
+    cells: [[Cell; W]; H],
+    claim_steps: [[u32; W]; H]
+}
+
+impl<const W: usize, const H: usize> FixedGrid<W, H> {
+    pub fn new(x_offset: isize, y_offset: isize) -> Self {
+        FixedGrid {
+            bounds: BoundingBox::new(x_offset, y_offset, W, H),
+            ties: TieBreak::default(),
+            cells: [[Cell::new(); W]; H],
+            claim_steps: [[NO_STEP; W]; H]
+        }
+    }
+
+    // Rule for cells at the same distance from more than one site, as with
+    // VoronoiBuilder::ties
+    pub fn ties(mut self, rule: TieBreak) -> Self {
+        self.ties = rule;
+        self
+    }
+
+    pub fn tie_break(&self) -> TieBreak {
+        self.ties
+    }
+
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    // Settles every cell between all of the sites, giving the same owners as
+    // VoronoiTesselation::compute_exact() on a tesselation of these bounds
+    // built with OutOfBoundsPolicy::Clamp, so sites outside of the grid still
+    // claim the cells closest to them. compute() gives the same owners wherever
+    // growing the regions is exact, see VoronoiTesselation::verify. Every cell
+    // is marked as claimed in step 0.
+    pub fn solve<S, M>(&mut self, sites: &[S]) -> Result<(), VoronoiError>
+    where
+        S: Site,
+        M: Metric
+    {
+        check_site_count(sites.len())?;
+
+        let cells = self.cells.as_flattened_mut().iter_mut();
+        let claim_steps = self.claim_steps.as_flattened_mut().iter_mut();
+        for (position, (cell, claim_step)) in cells.zip(claim_steps).enumerate() {
+            let idx = self.bounds.idx_at(position);
+            let candidates = sites
+                .iter()
+                .enumerate()
+                .map(|(position, site)| (SiteOwner(position as u32), position, site));
+
+            cell.reset();
+            *claim_step = NO_STEP;
+            match resolve_claims::<S, M, _>(idx, None, candidates, self.ties) {
+                Resolution::Claimed(owner) => {
+                    cell.restore_owner(owner);
+                    *claim_step = 0;
+                },
+                Resolution::Tied(tied_with) => cell.mark_tied(tied_with),
+                Resolution::Kept => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // Cell at the coordinates, None if they are outside of the grid
+    pub fn get<I: Into<GridIdx>>(&self, idx: I) -> Option<&Cell> {
+        let position = self.bounds.linear_idx(idx.into())?;
+        self.cells.as_flattened().get(position)
+    }
+
+    // Owner of the cell at the coordinates, None if the cell is unowned or
+    // outside of the grid
+    pub fn owner_at(&self, coordinates: (isize, isize)) -> Option<SiteOwner> {
+        self.get(coordinates)?.owner()
+    }
+
+    pub fn cells(&self) -> Cells<'_> {
+        Cells::new(&self.bounds, self.cells.as_flattened(), self.claim_steps.as_flattened())
+    }
+
+    pub fn clear(&mut self) {
+        self.cells = [[Cell::new(); W]; H];
+        self.claim_steps = [[NO_STEP; W]; H];
+    }
+}
+
+impl<const W: usize, const H: usize> Index<GridIdx> for FixedGrid<W, H> {
+    type Output = Cell;
+
+    fn index(&self, idx: GridIdx) -> &Self::Output {
+        self.get(idx).expect("Coordinates are outside of the grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::{OutOfBoundsPolicy, VoronoiBuilder, VoronoiTesselation};
+    use crate::metric::{Euclidean, Manhattan};
+    use crate::site::Point;
+
+    // Owners of a tesselation as positions in the builder input
+    fn input_labels<S: Site, M: Metric>(tess: &VoronoiTesselation<S, M>) -> Vec<Option<usize>> {
+        let label = |owner: Option<SiteOwner>| owner.and_then(|owner| tess.original_index(owner));
+        tess.cells().iter().map(|(_, cell)| label(cell.owner())).collect()
+    }
+
+    #[test]
+    fn solve_fixed_grid() {
+        let sites = [(1, 1, 1f32), (6, 2, 1f32), (3, 5, 1f32)];
+
+        let mut grid: FixedGrid<8, 7> = FixedGrid::new(0, 0);
+        grid.solve::<_, Euclidean>(&sites).unwrap();
+
+        let mut tess = VoronoiBuilder::new(sites.to_vec())
+            .bounds(*grid.bounds())
+            .build()
+            .unwrap();
+        tess.compute();
+
+        for (idx, cell) in tess.cells() {
+            if let Some(owner) = cell.owner() {
+                let expected = tess.original_index(owner).unwrap();
+                assert_eq!(grid.owner_at(idx.coordinates()), Some(SiteOwner(expected as u32)));
+                assert_eq!(grid.cells().claim_step(grid.bounds().linear_idx(idx).unwrap()), Some(0));
+            }
+        }
+
+        assert_eq!(grid.owner_at((8, 0)), None);
+        grid.clear();
+        assert!(grid.cells().iter().all(|(_, cell)| cell.owner().is_none()));
+    }
+
+    #[test]
+    fn solve_matches_tesselation() {
+        // Manhattan distances between integer sites tie all over the grid, one
+        // site lies outside of it
+        let sites = [(4, 0, 1f32), (0, 4, 1f32), (6, 6, 1f32), (2, 2, 1f32), (9, 3, 1f32)];
+
+        for rule in [TieBreak::FirstSite, TieBreak::Unowned] {
+            let mut grid: FixedGrid<8, 7> = FixedGrid::new(0, 0).ties(rule);
+            grid.solve::<_, Manhattan>(&sites).unwrap();
+
+            let mut tess = VoronoiBuilder::new(sites.to_vec())
+                .metric::<Manhattan>()
+                .bounds(*grid.bounds())
+                .out_of_bounds(OutOfBoundsPolicy::Clamp)
+                .ties(rule)
+                .build()
+                .unwrap();
+            let solved: Vec<Option<usize>> =
+                grid.cells().iter().map(|(_, cell)| cell.owner().map(|owner| owner.0 as usize)).collect();
+            assert!(rule == TieBreak::FirstSite || solved.iter().any(Option::is_none));
+
+            tess.compute_exact();
+            assert_eq!(solved, input_labels(&tess));
+
+            // Growing the regions agrees on these sites as well, as long as
+            // ties do not stop the growth behind them
+            if rule == TieBreak::FirstSite {
+                tess.reset_grid();
+                tess.init_sites();
+                tess.compute();
+                assert_eq!(solved, input_labels(&tess));
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::discrete_voronoi::VoronoiTesselation;
+use crate::grid::GridIdx;
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+// Step to one of the 8 surrounding cells, north being towards smaller y
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest
+}
+
+impl Direction {
+    // Clockwise from north
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest
+    ];
+
+    // Position in ALL, for compact rasters
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Direction> {
+        Direction::ALL.get(code as usize).copied()
+    }
+
+    // Change in x and y when taking the step
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1)
+        }
+    }
+
+    // The offset scaled to length 1
+    pub fn unit_vector(self) -> (f64, f64) {
+        let (x, y) = self.offset();
+        let length = ((x * x + y * y) as f64).sqrt();
+        (x as f64 / length, y as f64 / length)
+    }
+}
+
+// Step from every cell towards its owner in row-major order, to the
+// surrounding cell of the same region closest to the site under the metric.
+// Every step gets closer to the site without leaving the region, so
+// following the field never loops and leads towards the site.
+// Ties go to the first direction clockwise from north. None for unowned
+// cells, the cell of the site and cells where no step gets closer.
+pub fn direction_field<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<Option<Direction>>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let bounds = *tesselation.bounds();
+
+    tesselation
+        .cells()
+        .iter()
+        .map(|(idx, cell)| {
+            let owner = cell.owner()?;
+            let site = tesselation.site(owner)?;
+            let (x, y) = idx.coordinates();
+            let mut best: Option<(Direction, f64)> = None;
+            let current: f64 = M::distance(site, &idx).into();
+
+            for direction in Direction::ALL {
+                let (dx, dy) = direction.offset();
+                let next = GridIdx::from((x + dx, y + dy));
+                if !next.inside(&bounds) || tesselation[next].owner() != Some(owner) {
+                    continue;
+                }
+
+                let distance: f64 = M::distance(site, &next).into();
+                if distance < best.map_or(current, |(_, closest)| closest) {
+                    best = Some((direction, distance));
+                }
+            }
+
+            best.map(|(direction, _)| direction)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn flow_towards_owners() {
+        let sites = vec![(0, 0, 1f32), (4, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 5, 3))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let field = direction_field(&tess);
+        assert_eq!(field[0], None);
+        assert_eq!(field[1], Some(Direction::West));
+        assert_eq!(field[1 + 5], Some(Direction::NorthWest));
+        assert_eq!(field[3 + 2 * 5], Some(Direction::NorthEast));
+        assert_eq!(field[4 + 2 * 5], Some(Direction::North));
+
+        // Following the field from any owned cell ends up at its site
+        for (position, (_, cell)) in tess.cells().iter().enumerate() {
+            let mut position = position;
+            while let Some(direction) = field[position] {
+                let (dx, dy) = direction.offset();
+                position = (position as isize + dx + dy * 5) as usize;
+            }
+            let site = tess.site(cell.owner().unwrap()).unwrap();
+            assert_eq!(position as isize, site.0 + site.1 * 5);
+        }
+
+        assert_eq!(Direction::from_code(Direction::SouthWest.code()), Some(Direction::SouthWest));
+        assert_eq!(Direction::East.unit_vector(), (1.0, 0.0));
+    }
+}
@@ -1,5 +1,6 @@
-use discrete_voronoi::SiteOwner;
-use site::{Point, Site};
+use crate::discrete_voronoi::SiteOwner;
+use crate::error::VoronoiError;
+use crate::site::{Point, Site};
 
 use std::ops::{Index, IndexMut};
 
@@ -21,12 +22,15 @@ impl BoundingBox {
         }
     }
 
-    pub fn fit_to_sites<S: Site>(sites: &Vec<S>) -> Self {
-        assert!(!sites.is_empty(), "Sites must not be empty");
-        let mut min_x = isize::max_value();
-        let mut max_x = isize::min_value();
-        let mut min_y = isize::max_value();
-        let mut max_y = isize::min_value();
+    pub fn fit_to_sites<S: Site>(sites: &[S]) -> Result<Self, VoronoiError> {
+        if sites.is_empty() {
+            return Err(VoronoiError::NoSites);
+        }
+
+        let mut min_x = isize::MAX;
+        let mut max_x = isize::MIN;
+        let mut min_y = isize::MAX;
+        let mut max_y = isize::MIN;
 
         for site in sites {
             let (x, y) = site.coordinates();
@@ -48,36 +52,129 @@ impl BoundingBox {
             }
         }
 
-        let width = (max_x - min_x + 1) as usize;
-        let height = (max_y - min_y + 1) as usize;
+        // The difference of the extremes always fits a usize, adding the last
+        // cell and multiplying the sides out may not
+        let width = max_x.abs_diff(min_x).checked_add(1).ok_or(VoronoiError::BoundsOverflow)?;
+        let height = max_y.abs_diff(min_y).checked_add(1).ok_or(VoronoiError::BoundsOverflow)?;
+        if width.checked_mul(height).is_none() {
+            return Err(VoronoiError::BoundsOverflow);
+        }
 
         let x_offset = min_x;
         let y_offset = min_y;
 
-        BoundingBox {
+        Ok(BoundingBox {
             height,
             width,
             x_offset,
             y_offset
+        })
+    }
+
+    // Checks that the bounds have cells, that their cells can be counted in a
+    // usize and that the coordinates of every cell fit in an isize
+    pub fn validate(&self) -> Result<(), VoronoiError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(VoronoiError::ZeroAreaBounds);
+        }
+
+        let fits = self.width.checked_mul(self.height).is_some()
+            && self.x_offset.checked_add_unsigned(self.width - 1).is_some()
+            && self.y_offset.checked_add_unsigned(self.height - 1).is_some();
+        if fits { Ok(()) } else { Err(VoronoiError::BoundsOverflow) }
+    }
+
+    // Smallest bounds holding these bounds and all of the cells
+    pub fn including<I>(&self, cells: I) -> Result<BoundingBox, VoronoiError>
+    where
+        I: IntoIterator<Item = GridIdx>
+    {
+        let (mut min_x, mut min_y) = (self.x_offset, self.y_offset);
+        let mut max_x = self.x_offset.checked_add_unsigned(self.width - 1).ok_or(VoronoiError::BoundsOverflow)?;
+        let mut max_y = self.y_offset.checked_add_unsigned(self.height - 1).ok_or(VoronoiError::BoundsOverflow)?;
+
+        for GridIdx(x, y) in cells {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
         }
+
+        let width = max_x.abs_diff(min_x).checked_add(1).ok_or(VoronoiError::BoundsOverflow)?;
+        let height = max_y.abs_diff(min_y).checked_add(1).ok_or(VoronoiError::BoundsOverflow)?;
+        let bounds = BoundingBox::new(min_x, min_y, width, height);
+        bounds.validate()?;
+
+        Ok(bounds)
     }
 
+    // The cell of the bounds closest to the coordinates
+    pub fn clamp_idx(&self, idx: GridIdx) -> GridIdx {
+        let clamp = |value: isize, offset: isize, length: usize| {
+            value.clamp(offset, offset.saturating_add_unsigned(length - 1))
+        };
+
+        GridIdx(clamp(idx.0, self.x_offset, self.width), clamp(idx.1, self.y_offset, self.height))
+    }
+
+    // Position of the coordinates relative to the top left corner, panics if
+    // they are outside of the bounds
     pub fn translate_idx(&self, idx: GridIdx) -> (usize, usize) {
-        let x = (idx.0 - self.x_offset) as usize;
-        let y = (idx.1 - self.y_offset) as usize;
-        (x, y)
+        self.try_translate_idx(idx).expect("coordinates outside of the bounds")
+    }
+
+    // Position of the coordinates relative to the top left corner, None if
+    // they are outside of the bounds
+    pub fn try_translate_idx(&self, idx: GridIdx) -> Option<(usize, usize)> {
+        if idx.inside(self) {
+            Some((idx.0.abs_diff(self.x_offset), idx.1.abs_diff(self.y_offset)))
+        } else {
+            None
+        }
+    }
+
+    // Position of the coordinates in a row-major buffer of the cells, None if
+    // they are outside of the bounds
+    pub(crate) fn linear_idx(&self, idx: GridIdx) -> Option<usize> {
+        // Valid bounds hold at most usize::MAX cells, so this cannot overflow
+        self.try_translate_idx(idx).map(|(x, y)| x + y * self.width)
+    }
+
+    // Coordinates of the cell at the position of a row-major buffer, the
+    // inverse of linear_idx()
+    pub(crate) fn idx_at(&self, position: usize) -> GridIdx {
+        debug_assert!(position / self.width < self.height);
+        GridIdx(
+            self.x_offset.wrapping_add_unsigned(position % self.width),
+            self.y_offset.wrapping_add_unsigned(position / self.width)
+        )
     }
 
     pub fn dimensions(&self) -> (usize, usize) {
         (self.width, self.height)
     }
 
-    pub fn coordinates_iter(&self) -> BoundedCoordinatesIter {
+    pub fn offset(&self) -> (isize, isize) {
+        (self.x_offset, self.y_offset)
+    }
+
+    // True if every cell of the other bounds is also inside these bounds
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        // Far edges are compared in i128 so they cannot overflow
+        let end = |offset: isize, length: usize| offset as i128 + length as i128;
+
+        self.x_offset <= other.x_offset
+            && self.y_offset <= other.y_offset
+            && end(other.x_offset, other.width) <= end(self.x_offset, self.width)
+            && end(other.y_offset, other.height) <= end(self.y_offset, self.height)
+    }
+
+    pub fn coordinates_iter(&self) -> BoundedCoordinatesIter<'_> {
         BoundedCoordinatesIter(self, Some(GridIdx(self.x_offset, self.y_offset)))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BoundedCoordinatesIter<'a>(&'a BoundingBox, Option<GridIdx>);
 
 impl<'a> Iterator for BoundedCoordinatesIter<'a> {
@@ -87,8 +184,8 @@ impl<'a> Iterator for BoundedCoordinatesIter<'a> {
         let last = self.1;
 
         if let Some(last_idx) = self.1 {
-            if (last_idx.0 - self.0.x_offset + 1) as usize >= self.0.width {
-                if (last_idx.1 - self.0.y_offset + 1) as usize >= self.0.height {
+            if last_idx.0.abs_diff(self.0.x_offset) + 1 >= self.0.width {
+                if last_idx.1.abs_diff(self.0.y_offset) + 1 >= self.0.height {
                     self.1 = None; // Bottom right corner
                 } else {
                     self.1 = Some(GridIdx(self.0.x_offset, last_idx.1 + 1)); // next row
@@ -102,6 +199,9 @@ impl<'a> Iterator for BoundedCoordinatesIter<'a> {
     }
 }
 
+// Coordinates of a cell. They are always isize, whatever coordinate type the
+// sites use; cells do not store them, so only the frontier and the iterators
+// pay for their width.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GridIdx(isize, isize);
 
@@ -111,10 +211,10 @@ impl GridIdx {
     }
 
     pub fn inside(&self, bounds: &BoundingBox) -> bool {
-        let adjusted_x = self.0 - bounds.x_offset;
-        let adjusted_y = self.1 - bounds.y_offset;
-
-        0 <= adjusted_x && adjusted_x < bounds.width as isize && 0 <= adjusted_y && adjusted_y < bounds.height as isize
+        bounds.x_offset <= self.0
+            && self.0.abs_diff(bounds.x_offset) < bounds.width
+            && bounds.y_offset <= self.1
+            && self.1.abs_diff(bounds.y_offset) < bounds.height
     }
 }
 
@@ -130,6 +230,12 @@ impl From<(isize, isize)> for GridIdx {
     }
 }
 
+impl From<(i32, i32)> for GridIdx {
+    fn from(src: (i32, i32)) -> Self {
+        GridIdx(src.0 as isize, src.1 as isize)
+    }
+}
+
 const MAX_DIRECTION: u8 = 4;
 #[derive(Debug)]
 pub struct GridIdxNeighborIter<'a>(&'a GridIdx, u8, &'a BoundingBox);
@@ -142,17 +248,19 @@ impl<'a> Iterator for GridIdxNeighborIter<'a> {
             None
         } else {
             loop {
+                let GridIdx(x, y) = *self.0;
+                // Steps past the end of the coordinate range have no neighbour
                 let possible = match self.1 {
-                    0 => GridIdx((self.0).0, (self.0).1 + 1), // north
-                    1 => GridIdx((self.0).0 + 1, (self.0).1), // east
-                    2 => GridIdx((self.0).0, (self.0).1 - 1), // south
-                    3 => GridIdx((self.0).0 - 1, (self.0).1), // west
+                    0 => y.checked_add(1).map(|y| GridIdx(x, y)), // north
+                    1 => x.checked_add(1).map(|x| GridIdx(x, y)), // east
+                    2 => y.checked_sub(1).map(|y| GridIdx(x, y)), // south
+                    3 => x.checked_sub(1).map(|x| GridIdx(x, y)), // west
                     x if x >= MAX_DIRECTION => break None,
                     _ => unreachable!()
                 };
 
                 self.1 += 1;
-                if possible.inside(self.2) {
+                if let Some(possible) = possible.filter(|possible| possible.inside(self.2)) {
                     break Some(possible);
                 }
             }
@@ -160,69 +268,109 @@ impl<'a> Iterator for GridIdxNeighborIter<'a> {
     }
 }
 
-#[derive(Debug)]
+// Grid stored as two arrays, the cells and the steps that claimed them. The
+// claim steps are only read by exporters and analysis, so the growth and
+// every scan over the owners touch a single word per cell. Owner and flags
+// stay together in Cell rather than in arrays of their own: settling a claim
+// reads the owner, the tie and the contested flag of the same cell at once,
+// and Index, get() and cells() hand out &Cell, which needs all of a cell's
+// state in one place.
+#[derive(Debug, Clone)]
 pub struct Grid {
     bounds: BoundingBox,
-    data: Box<[Cell]>
+    data: Box<[Cell]>,
+    claim_steps: Box<[u32]>
 }
 
 impl Grid {
+    // Panics on bounds that do not pass BoundingBox::validate()
     pub fn new(bounds: BoundingBox) -> Self {
-        let mut data = Vec::with_capacity(bounds.width * bounds.height);
-        unsafe { data.set_len(bounds.width * bounds.height) }
-        for coord in bounds.coordinates_iter() {
-            let (x, y) = bounds.translate_idx(coord);
-            data[x + y * bounds.width] = Cell::new(coord);
+        if let Err(err) = bounds.validate() {
+            panic!("invalid grid bounds: {}", err);
         }
 
+        let (width, height) = bounds.dimensions();
+
+        #[cfg(feature = "rayon")]
+        let (data, claim_steps): (Vec<Cell>, Vec<u32>) = {
+            use rayon::prelude::*;
+
+            (0..width * height).into_par_iter().map(|_| (Cell::new(), NO_STEP)).unzip()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let (data, claim_steps) = (vec![Cell::new(); width * height], vec![NO_STEP; width * height]);
+
         Grid {
             bounds,
-            data: data.into_boxed_slice()
+            data: data.into_boxed_slice(),
+            claim_steps: claim_steps.into_boxed_slice()
         }
     }
 
     pub fn clear(&mut self) {
-        for coord in self.bounds.coordinates_iter() {
-            let (x, y) = self.bounds.translate_idx(coord);
-            let ref mut cell = self.data[x + y * self.bounds.width];
-            cell.contested = false;
-            cell.owner = None;
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            self.data.par_iter_mut().for_each(Cell::reset);
+            self.claim_steps.par_iter_mut().for_each(|step| *step = NO_STEP);
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            self.data.iter_mut().for_each(Cell::reset);
+            self.claim_steps.fill(NO_STEP);
         }
     }
 
-    pub fn bounds(&self) -> &BoundingBox {
-        &self.bounds
+    // Gives the cell to the owner, claimed in the step
+    pub(crate) fn set_owner(&mut self, idx: GridIdx, owner: SiteOwner, step: u32) {
+        let position = self.position(idx);
+        self.data[position].restore_owner(owner);
+        self.claim_steps[position] = step;
     }
 
-    pub fn claim_cells(
-        &mut self,
-        indices: &Vec<GridIdx>,
-        claimant: SiteOwner
-    ) -> (Vec<GridIdx>, Vec<(GridIdx, SiteOwner)>) {
-        let mut contested_cells = Vec::new();
-        let mut claimed_cells = Vec::new();
+    // Back to the unclaimed, uncontested state of a fresh grid
+    pub(crate) fn reset(&mut self, idx: GridIdx) {
+        let position = self.position(idx);
+        self.data[position].reset();
+        self.claim_steps[position] = NO_STEP;
+    }
 
-        for idx in indices {
-            let ref mut cell = self[*idx];
-            let same_owner = cell.owner.map_or(false, |cell| cell == claimant);
-            let contested = cell.contested;
-            let empty = cell.owner.is_none();
+    // Leaves the cell unowned after a tie with the site, see Cell::mark_tied
+    pub(crate) fn mark_tied(&mut self, idx: GridIdx, tied_with: SiteOwner) {
+        let position = self.position(idx);
+        self.data[position].mark_tied(tied_with);
+        self.claim_steps[position] = NO_STEP;
+    }
 
-            if !same_owner {
-                if !contested && empty {
-                    cell.owner = Some(claimant);
+    fn position(&self, idx: GridIdx) -> usize {
+        self.bounds.linear_idx(idx).expect("coordinates outside of the grid")
+    }
 
-                    claimed_cells.push(*idx);
-                } else if !empty {
-                    let old_owner = cell.owner.take().unwrap();
-                    cell.contested = true;
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
 
-                    contested_cells.push((*idx, old_owner));
-                }
-            }
-        }
+    // Grows the grid to the new bounds, which must contain the current ones,
+    // keeping the state of the existing cells
+    pub fn expand(&mut self, bounds: BoundingBox) {
+        debug_assert!(bounds.contains(&self.bounds));
+
+        let (data, claim_steps): (Vec<Cell>, Vec<u32>) = bounds
+            .coordinates_iter()
+            .map(|coord| match self.bounds.linear_idx(coord) {
+                Some(position) => (self.data[position], self.claim_steps[position]),
+                None => (Cell::new(), NO_STEP)
+            })
+            .unzip();
+
+        self.bounds = bounds;
+        self.data = data.into_boxed_slice();
+        self.claim_steps = claim_steps.into_boxed_slice();
+    }
 
-        (claimed_cells, contested_cells)
+    pub fn cells(&self) -> Cells<'_> {
+        Cells::new(&self.bounds, &self.data, &self.claim_steps)
     }
 
     pub fn into_raw(self) -> Box<[Cell]> {
@@ -234,43 +382,264 @@ impl Index<GridIdx> for Grid {
     type Output = Cell;
 
     fn index(&self, idx: GridIdx) -> &Self::Output {
-        let (x, y) = self.bounds.translate_idx(idx);
-        &self.data[x + y * self.bounds.width]
+        &self.data[self.position(idx)]
     }
 }
 
 impl IndexMut<GridIdx> for Grid {
     fn index_mut(&mut self, idx: GridIdx) -> &mut Self::Output {
-        let (x, y) = self.bounds.translate_idx(idx);
-        &mut self.data[x + y * self.bounds.width]
+        let position = self.position(idx);
+        &mut self.data[position]
     }
 }
 
+// The cells of a grid in row-major order. Cells do not store their
+// coordinates, they follow from the position and come along when iterating.
+#[derive(Debug, Clone, Copy)]
+pub struct Cells<'a> {
+    bounds: &'a BoundingBox,
+    data: &'a [Cell],
+    claim_steps: &'a [u32]
+}
+
+impl<'a> Cells<'a> {
+    pub(crate) fn new(bounds: &'a BoundingBox, data: &'a [Cell], claim_steps: &'a [u32]) -> Self {
+        debug_assert_eq!(data.len(), claim_steps.len());
+        Cells { bounds, data, claim_steps }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    // Always false, a grid has at least one cell
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // Cell at the position in row-major order
+    pub fn get(&self, position: usize) -> Option<&'a Cell> {
+        self.data.get(position)
+    }
+
+    // The cells without their coordinates, for working on whole rows
+    pub fn as_slice(&self) -> &'a [Cell] {
+        self.data
+    }
+
+    // Step of the computation in which the current owner claimed the cell at
+    // the position in row-major order, 0 being the initial placement of the
+    // sites
+    pub fn claim_step(&self, position: usize) -> Option<u32> {
+        let owner = self.data.get(position)?.owner();
+        match self.claim_steps[position] {
+            NO_STEP => None,
+            step => owner.and(Some(step))
+        }
+    }
+
+    // Coordinates of the cell at the position in row-major order
+    pub fn coordinates(&self, position: usize) -> Option<GridIdx> {
+        (position < self.data.len()).then(|| self.bounds.idx_at(position))
+    }
+
+    pub fn iter(&self) -> CellsIter<'a> {
+        CellsIter {
+            coordinates: self.bounds.coordinates_iter(),
+            cells: self.data.iter()
+        }
+    }
+}
+
+impl<'a> Index<usize> for Cells<'a> {
+    type Output = Cell;
+
+    fn index(&self, position: usize) -> &Self::Output {
+        &self.data[position]
+    }
+}
+
+impl<'a> IntoIterator for Cells<'a> {
+    type Item = (GridIdx, &'a Cell);
+    type IntoIter = CellsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Cells along with their coordinates in row-major order
 #[derive(Debug, Clone)]
+pub struct CellsIter<'a> {
+    coordinates: BoundedCoordinatesIter<'a>,
+    cells: std::slice::Iter<'a, Cell>
+}
+
+impl<'a> Iterator for CellsIter<'a> {
+    type Item = (GridIdx, &'a Cell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.coordinates.next()?, self.cells.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CellsIter<'a> {}
+
+const CONTESTED: u32 = 1 << 31;
+const TIED: u32 = 1 << 30;
+const FLAGS: u32 = CONTESTED | TIED;
+const NO_OWNER: u32 = TIED - 1;
+pub(crate) const NO_STEP: u32 = u32::MAX;
+
+// Owner ids have to stay below the id standing in for no owner
+pub(crate) const MAX_SITES: usize = NO_OWNER as usize;
+
+// Owner and flags are packed into one word, the owner id taking the low 30
+// bits with all ones standing in for no owner. A tied cell has no owner and
+// keeps the id of one of the tied sites instead, so later claims can be
+// measured against the distance of the tie. Coordinates are not stored, they
+// follow from the position of the cell in the grid, and the grid keeps the
+// claim steps apart, see Cells::claim_step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Cell {
-    coordinates: GridIdx,
-    contested: bool,
-    owner: Option<SiteOwner>
+    state: u32
 }
 
 impl Cell {
-    fn new(coordinates: GridIdx) -> Self {
-        Cell {
-            coordinates,
-            contested: false,
-            owner: None
-        }
+    pub(crate) fn new() -> Self {
+        Cell { state: NO_OWNER }
+    }
+
+    // Back to a fresh cell, the grid resets the claim step along with it
+    pub(crate) fn reset(&mut self) {
+        self.state = NO_OWNER;
+    }
+
+    // Hand the cell back to an owner that lost it to a contest, keeping the
+    // step of the original claim
+    pub fn restore_owner(&mut self, old_owner: SiteOwner) {
+        debug_assert!(old_owner.0 < NO_OWNER);
+        self.state = (self.state & CONTESTED) | old_owner.0;
+    }
+
+    // Flags that more than one site claimed the cell, keeping the owner
+    pub(crate) fn mark_contested(&mut self) {
+        self.state |= CONTESTED;
+    }
+
+    // Leaves the cell contested without an owner after a tie with the site.
+    // A strictly closer site can still claim the cell later on.
+    pub(crate) fn mark_tied(&mut self, tied_with: SiteOwner) {
+        debug_assert!(tied_with.0 < NO_OWNER);
+        self.state = CONTESTED | TIED | tied_with.0;
     }
 
-    pub fn set_owner(&mut self, new_owner: SiteOwner) {
-        self.owner = Some(new_owner);
+    pub fn owner(&self) -> Option<SiteOwner> {
+        match self.state & !FLAGS {
+            _ if self.state & TIED != 0 => None,
+            NO_OWNER => None,
+            owner => Some(SiteOwner(owner))
+        }
     }
 
-    pub fn owner(&self) -> &Option<SiteOwner> {
-        &self.owner
+    // One of the sites the cell is tied between, if a tie left it unowned
+    pub(crate) fn tied_with(&self) -> Option<SiteOwner> {
+        match self.state & TIED {
+            0 => None,
+            _ => Some(SiteOwner(self.state & !FLAGS))
+        }
     }
 
     pub fn contested(&self) -> bool {
-        self.contested
+        self.state & CONTESTED != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translate_coordinates_at_extremes() {
+        let bounds = BoundingBox::new(isize::MAX - 2, isize::MIN, 3, 2);
+        assert_eq!(bounds.validate(), Ok(()));
+        assert!(GridIdx(isize::MAX, isize::MIN + 1).inside(&bounds));
+        assert!(!GridIdx(isize::MIN, isize::MIN).inside(&bounds));
+        assert_eq!(bounds.try_translate_idx(GridIdx(isize::MAX, isize::MIN)), Some((2, 0)));
+        assert_eq!(bounds.try_translate_idx(GridIdx(0, 0)), None);
+        assert_eq!(bounds.coordinates_iter().count(), 6);
+
+        // No neighbour past the end of the coordinate range
+        let corner = GridIdx(isize::MAX, isize::MIN);
+        let neighbors: Vec<GridIdx> = corner.neighbors(&bounds).collect();
+        assert_eq!(neighbors, vec![GridIdx(isize::MAX, isize::MIN + 1), GridIdx(isize::MAX - 1, isize::MIN)]);
+        assert!(bounds.contains(&BoundingBox::new(isize::MAX, isize::MIN, 1, 1)));
+
+        assert_eq!(BoundingBox::new(isize::MAX, 0, 2, 1).validate(), Err(VoronoiError::BoundsOverflow));
+        assert_eq!(BoundingBox::new(0, 0, usize::MAX, 2).validate(), Err(VoronoiError::BoundsOverflow));
+        assert_eq!(BoundingBox::new(0, 0, 0, 2).validate(), Err(VoronoiError::ZeroAreaBounds));
+    }
+
+    #[test]
+    fn fit_bounds_to_extreme_sites() {
+        let sites = [(-3isize, 2isize, 1f32), (4, -1, 1f32)];
+        assert_eq!(BoundingBox::fit_to_sites(&sites), Ok(BoundingBox::new(-3, -1, 8, 4)));
+
+        let empty: [(isize, isize, f32); 0] = [];
+        assert_eq!(BoundingBox::fit_to_sites(&empty), Err(VoronoiError::NoSites));
+
+        let wide = [(isize::MIN, 0isize, 1f32), (isize::MAX, 0, 1f32)];
+        assert_eq!(BoundingBox::fit_to_sites(&wide), Err(VoronoiError::BoundsOverflow));
+
+        let huge = [(isize::MIN, isize::MIN, 1f32), (0, 0, 1f32)];
+        assert_eq!(BoundingBox::fit_to_sites(&huge), Err(VoronoiError::BoundsOverflow));
+    }
+
+    #[test]
+    fn derive_cell_coordinates_from_position() {
+        let bounds = BoundingBox::new(-2, 3, 3, 2);
+        let grid = Grid::new(bounds);
+        let cells = grid.cells();
+
+        let coordinates: Vec<GridIdx> = cells.iter().map(|(idx, _)| idx).collect();
+        assert_eq!(coordinates, bounds.coordinates_iter().collect::<Vec<_>>());
+        assert_eq!(cells.iter().len(), 6);
+        assert_eq!(cells.coordinates(4), Some(GridIdx(-1, 4)));
+        assert_eq!(cells.coordinates(6), None);
+        assert_eq!(bounds.linear_idx(bounds.idx_at(5)), Some(5));
+    }
+
+    #[test]
+    fn pack_cell_state() {
+        assert_eq!(std::mem::size_of::<Cell>(), 4);
+
+        let idx = GridIdx(1, 0);
+        let mut grid = Grid::new(BoundingBox::new(0, 0, 2, 1));
+        let state = |grid: &Grid| (grid[idx].owner(), grid[idx].contested(), grid.cells().claim_step(1));
+        assert_eq!(state(&grid), (None, false, None));
+
+        grid.set_owner(idx, SiteOwner(7), 3);
+        grid[idx].mark_contested();
+        assert_eq!(state(&grid), (Some(SiteOwner(7)), true, Some(3)));
+
+        grid.mark_tied(idx, SiteOwner(7));
+        assert_eq!(state(&grid), (None, true, None));
+        assert_eq!(grid[idx].tied_with(), Some(SiteOwner(7)));
+
+        grid.set_owner(idx, SiteOwner(2), 5);
+        assert_eq!(state(&grid), (Some(SiteOwner(2)), true, Some(5)));
+        assert_eq!(grid[idx].tied_with(), None);
+
+        // Growing the grid keeps the claim steps with their cells
+        grid.expand(BoundingBox::new(-1, 0, 3, 2));
+        assert_eq!(grid.cells().claim_step(2), Some(5));
+
+        grid.reset(GridIdx(1, 0));
+        assert_eq!(grid.cells().claim_step(2), None);
+        assert!(!grid[GridIdx(1, 0)].contested());
     }
 }
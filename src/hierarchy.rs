@@ -0,0 +1,202 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiBuilder};
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::site::Site;
+
+// Tesselations nested inside each other, every region of a level is split up
+// by the sites of the next level that fall inside of it. Owners at a level
+// are the positions of the sites in the input for that level.
+#[derive(Debug, Clone)]
+pub struct Hierarchy<S> {
+    bounds: BoundingBox,
+    sites: Vec<Vec<S>>,
+    // Owner of every cell per level in row-major order
+    labels: Vec<Vec<Option<SiteOwner>>>,
+    // Region of the level above holding each site, None for the top level
+    parents: Vec<Vec<Option<SiteOwner>>>
+}
+
+impl<S> Hierarchy<S>
+where
+    S: Site + Clone
+{
+    // Computes the top level tesselation over the bounds
+    pub fn new<M: Metric>(bounds: BoundingBox, sites: Vec<S>) -> Result<Self, VoronoiError> {
+        let labels = label_cells::<S, M>(bounds, &sites, bounds, |_| true)?;
+        let parents = vec![None; sites.len()];
+
+        Ok(Hierarchy {
+            bounds,
+            sites: vec![sites],
+            labels: vec![labels],
+            parents: vec![parents]
+        })
+    }
+
+    // Adds a level below the current deepest one. Every site goes to the region
+    // its cell belongs to and the sites of a region split up only the cells of
+    // that region, using the given metric. Regions without any site have no
+    // owner at the new level. Returns the positions of the sites that are not
+    // inside of any region, which are left out.
+    pub fn subdivide<M: Metric>(&mut self, sites: Vec<S>) -> Result<Vec<usize>, VoronoiError> {
+        let (width, _) = self.bounds.dimensions();
+        let parent_labels = self.labels.last().expect("a hierarchy always has a top level");
+
+        let mut outside = Vec::new();
+        let parents: Vec<Option<SiteOwner>> = sites
+            .iter()
+            .enumerate()
+            .map(|(index, site)| {
+                let parent = self.bounds.linear_idx(GridIdx::from(site.coordinates())).and_then(|at| parent_labels[at]);
+                if parent.is_none() {
+                    outside.push(index);
+                }
+                parent
+            })
+            .collect();
+
+        let mut labels = vec![None; parent_labels.len()];
+        let mut regions: Vec<SiteOwner> = parents.iter().flatten().copied().collect();
+        regions.sort_unstable();
+        regions.dedup();
+
+        for region in regions {
+            // Smallest box around the cells of the region
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (usize::MAX, usize::MAX, 0, 0);
+            for (position, _) in parent_labels.iter().enumerate().filter(|(_, &label)| label == Some(region)) {
+                let (x, y) = (position % width, position / width);
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+            }
+            let (x_offset, y_offset) = self.bounds.offset();
+            let region_bounds = BoundingBox::new(
+                x_offset + min_x as isize,
+                y_offset + min_y as isize,
+                max_x - min_x + 1,
+                max_y - min_y + 1
+            );
+
+            let (children, indices): (Vec<S>, Vec<usize>) = sites
+                .iter()
+                .zip(&parents)
+                .enumerate()
+                .filter(|(_, (_, &parent))| parent == Some(region))
+                .map(|(index, (site, _))| (site.clone(), index))
+                .unzip();
+            let in_region = |position: usize| parent_labels[position] == Some(region);
+            let region_labels = label_cells::<S, M>(region_bounds, &children, self.bounds, in_region)?;
+
+            for (label, found) in labels.iter_mut().zip(region_labels) {
+                if let Some(SiteOwner(child)) = found {
+                    *label = Some(SiteOwner(indices[child as usize] as u32));
+                }
+            }
+        }
+
+        self.sites.push(sites);
+        self.labels.push(labels);
+        self.parents.push(parents);
+
+        Ok(outside)
+    }
+
+    // Number of levels, 1 for just the top level
+    pub fn depth(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    // Sites of the level in input order, empty for unknown levels
+    pub fn sites(&self, level: usize) -> &[S] {
+        self.sites.get(level).map_or(&[], Vec::as_slice)
+    }
+
+    // Owner of every cell at the level in row-major order
+    pub fn labels(&self, level: usize) -> Option<&[Option<SiteOwner>]> {
+        self.labels.get(level).map(Vec::as_slice)
+    }
+
+    // Region of the level above that holds the site, None for the top level
+    // and for sites left out when subdividing
+    pub fn parent(&self, level: usize, owner: SiteOwner) -> Option<SiteOwner> {
+        self.parents.get(level)?.get(owner.0 as usize).copied().flatten()
+    }
+
+    pub fn owner_at(&self, level: usize, coordinates: (isize, isize)) -> Option<SiteOwner> {
+        let position = self.bounds.linear_idx(GridIdx::from(coordinates))?;
+        self.labels.get(level)?[position]
+    }
+
+    // Owners of the cell from the top level down, None if the cell is outside
+    // of the bounds or has no owner at some level
+    pub fn owner_path(&self, x: isize, y: isize) -> Option<Vec<SiteOwner>> {
+        let position = self.bounds.linear_idx(GridIdx::from((x, y)))?;
+        self.labels.iter().map(|labels| labels[position]).collect()
+    }
+}
+
+// Computes the tesselation of the sites within the region bounds and returns
+// the owner of every cell of the full bounds, as positions in the sites, for
+// the cells accepted by the filter
+fn label_cells<S, M>(
+    region: BoundingBox,
+    sites: &[S],
+    bounds: BoundingBox,
+    accept: impl Fn(usize) -> bool
+) -> Result<Vec<Option<SiteOwner>>, VoronoiError>
+where
+    S: Site + Clone,
+    M: Metric
+{
+    let mut tesselation = VoronoiBuilder::new(sites.iter().cloned()).metric::<M>().bounds(region).build()?;
+    tesselation.compute();
+
+    let (width, height) = bounds.dimensions();
+    let mut labels = vec![None; width * height];
+    for (idx, cell) in tesselation.cells() {
+        let position = match bounds.linear_idx(idx) {
+            Some(position) if accept(position) => position,
+            _ => continue
+        };
+        labels[position] = cell
+            .owner()
+            .and_then(|owner| tesselation.original_index(owner))
+            .map(|index| SiteOwner(index as u32));
+    }
+
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn nested_regions() {
+        // Two halves, the left one split into a top and a bottom part
+        let bounds = BoundingBox::new(0, 0, 8, 4);
+        let mut hierarchy = Hierarchy::new::<Manhattan>(bounds, vec![(1, 1, 1f32), (6, 1, 1f32)]).unwrap();
+        let outside = hierarchy.subdivide::<Manhattan>(vec![(1, 0, 1f32), (1, 3, 1f32), (9, 9, 1f32)]).unwrap();
+        assert_eq!(outside, vec![2]);
+        assert_eq!(hierarchy.depth(), 2);
+
+        assert_eq!(hierarchy.owner_path(0, 0), Some(vec![SiteOwner(0), SiteOwner(0)]));
+        assert_eq!(hierarchy.owner_path(3, 3), Some(vec![SiteOwner(0), SiteOwner(1)]));
+        // The right half has no sites of its own at the second level
+        assert_eq!(hierarchy.owner_path(6, 1), None);
+        assert_eq!(hierarchy.owner_at(0, (6, 1)), Some(SiteOwner(1)));
+        assert_eq!(hierarchy.owner_path(8, 0), None);
+
+        // Child sites only reach the cells of their own region
+        assert_eq!(hierarchy.owner_at(1, (4, 0)), None);
+        assert_eq!(hierarchy.parent(1, SiteOwner(1)), Some(SiteOwner(0)));
+        assert_eq!(hierarchy.parent(1, SiteOwner(2)), None);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::site::{Site, Weight};
+
+// How the influence of a site falls off with the distance from it
+#[derive(Debug, Clone, Copy)]
+pub enum Decay {
+    // Falls to zero at the radius
+    Linear { radius: f64 },
+    // Halves every half life
+    Exponential { half_life: f64 },
+    // Any function of the distance, negative values count as zero
+    Custom(fn(f64) -> f64)
+}
+
+impl Decay {
+    // Influence of a site with unit strength at the distance
+    pub fn apply(&self, distance: f64) -> f64 {
+        let influence = match *self {
+            Decay::Linear { radius } => 1.0 - distance / radius,
+            Decay::Exponential { half_life } => (-distance / half_life).exp2(),
+            Decay::Custom(decay) => decay(distance)
+        };
+
+        influence.max(0.0)
+    }
+}
+
+// Influence of every team on every cell, one row-major layer per team
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfluenceMap {
+    bounds: BoundingBox,
+    teams: usize,
+    values: Vec<f64>
+}
+
+impl InfluenceMap {
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    pub fn teams(&self) -> usize {
+        self.teams
+    }
+
+    // Influence of the team on every cell in row-major order, empty for
+    // unknown teams
+    pub fn layer(&self, team: usize) -> &[f64] {
+        let (width, height) = self.bounds.dimensions();
+        let cells = width * height;
+
+        self.values.get(team * cells..(team + 1) * cells).unwrap_or(&[])
+    }
+
+    // Influence of the team on the cell at the coordinates, None outside of
+    // the bounds or for unknown teams
+    pub fn influence_at(&self, coordinates: (isize, isize), team: usize) -> Option<f64> {
+        let (x, y) = self.bounds.try_translate_idx(GridIdx::from(coordinates))?;
+        let (width, _) = self.bounds.dimensions();
+
+        self.layer(team).get(x + y * width).copied()
+    }
+
+    // Team with the most influence on every cell in row-major order, None
+    // where no team has any. Ties go to the lower team.
+    pub fn owners(&self) -> Vec<Option<usize>> {
+        let (width, height) = self.bounds.dimensions();
+        let cells = width * height;
+
+        (0..cells)
+            .map(|cell| {
+                let mut strongest: Option<(usize, f64)> = None;
+                for team in 0..self.teams {
+                    let influence = self.values[team * cells + cell];
+                    if influence > strongest.map_or(0.0, |(_, best)| best) {
+                        strongest = Some((team, influence));
+                    }
+                }
+
+                strongest.map(|(team, _)| team)
+            })
+            .collect()
+    }
+}
+
+// Adds up the influence of every site on every cell of the bounds, grouped
+// by the team of the site. A site has its weight as strength, decaying with
+// the distance under the metric. Teams are numbered from 0 and there can be
+// no more teams than sites, a site in a team past that fails with
+// InvalidTeam. Every site is measured against every cell, without the region
+// growing of a tesselation since influence overlaps, so the cost is
+// O(sites * cells) and the map holds one layer of cells per team.
+pub fn influence_map<S, M, T>(
+    sites: &[S],
+    bounds: BoundingBox,
+    decay: Decay,
+    team_of: T
+) -> Result<InfluenceMap, VoronoiError>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>,
+    T: Fn(&S) -> usize
+{
+    bounds.validate()?;
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+
+    if let Some(index) = sites.iter().position(|site| team_of(site) >= sites.len()) {
+        return Err(VoronoiError::InvalidTeam(index));
+    }
+
+    let teams = sites.iter().map(&team_of).max().map_or(0, |team| team + 1);
+    let (width, height) = bounds.dimensions();
+    let cells = width * height;
+    let mut values = vec![0.0; teams * cells];
+
+    for site in sites {
+        let layer = &mut values[team_of(site) * cells..][..cells];
+        let strength = site.weight().to_f64();
+
+        for (value, idx) in layer.iter_mut().zip(bounds.coordinates_iter()) {
+            *value += strength * decay.apply(M::distance(site, &idx).into());
+        }
+    }
+
+    Ok(InfluenceMap { bounds, teams, values })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn accumulate_team_influence() {
+        let sites = [(0, 0, 1f32), (1, 0, 1f32), (5, 0, 3f32)];
+        let bounds = BoundingBox::new(0, 0, 6, 1);
+        let team = |site: &(isize, isize, f32)| if site.0 < 3 { 0 } else { 1 };
+
+        let map = influence_map::<_, Manhattan, _>(&sites, bounds, Decay::Linear { radius: 4.0 }, team).unwrap();
+
+        assert_eq!(map.teams(), 2);
+        assert_eq!(map.influence_at((0, 0), 0), Some(1.75));
+        assert_eq!(map.influence_at((2, 0), 1), Some(0.75));
+        assert_eq!(map.layer(1)[0], 0.0);
+        assert_eq!(map.owners(), vec![Some(0), Some(0), Some(0), Some(1), Some(1), Some(1)]);
+        assert_eq!(map.influence_at((6, 0), 0), None);
+
+        assert_eq!(Decay::Exponential { half_life: 2.0 }.apply(4.0), 0.25);
+
+        let far = |site: &(isize, isize, f32)| if site.0 == 5 { usize::MAX } else { 0 };
+        let result = influence_map::<_, Manhattan, _>(&sites, bounds, Decay::Linear { radius: 4.0 }, far);
+        assert_eq!(result, Err(VoronoiError::InvalidTeam(2)));
+    }
+}
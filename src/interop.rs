@@ -0,0 +1,138 @@
+// Point implementations for the geometry types of other crates, each behind
+// the feature of the same name
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use crate::site::{expect_coordinates, to_isize, Point};
+
+    impl Point for glam::IVec2 {
+        fn coordinates(&self) -> (isize, isize) {
+            expect_coordinates(self)
+        }
+
+        fn checked_coordinates(&self) -> Option<(isize, isize)> {
+            Some((to_isize(self.x)?, to_isize(self.y)?))
+        }
+
+        fn position(&self) -> (f64, f64) {
+            (self.x as f64, self.y as f64)
+        }
+    }
+
+    impl Point for glam::I64Vec2 {
+        fn coordinates(&self) -> (isize, isize) {
+            expect_coordinates(self)
+        }
+
+        fn checked_coordinates(&self) -> Option<(isize, isize)> {
+            Some((to_isize(self.x)?, to_isize(self.y)?))
+        }
+
+        fn position(&self) -> (f64, f64) {
+            (self.x as f64, self.y as f64)
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impls {
+    use crate::site::{expect_coordinates, to_isize, Point};
+
+    impl Point for nalgebra::Point2<i32> {
+        fn coordinates(&self) -> (isize, isize) {
+            expect_coordinates(self)
+        }
+
+        fn checked_coordinates(&self) -> Option<(isize, isize)> {
+            Some((to_isize(self.x)?, to_isize(self.y)?))
+        }
+
+        fn position(&self) -> (f64, f64) {
+            (self.x as f64, self.y as f64)
+        }
+    }
+
+    impl Point for nalgebra::Point2<i64> {
+        fn coordinates(&self) -> (isize, isize) {
+            expect_coordinates(self)
+        }
+
+        fn checked_coordinates(&self) -> Option<(isize, isize)> {
+            Some((to_isize(self.x)?, to_isize(self.y)?))
+        }
+
+        fn position(&self) -> (f64, f64) {
+            (self.x as f64, self.y as f64)
+        }
+    }
+}
+
+#[cfg(feature = "euclid")]
+mod euclid_impls {
+    use crate::site::{expect_coordinates, to_isize, Point};
+
+    macro_rules! impl_euclid_point {
+        ($($ty:ty),*) => {
+            $(
+                impl<U> Point for euclid::Point2D<$ty, U> {
+                    fn coordinates(&self) -> (isize, isize) {
+                        expect_coordinates(self)
+                    }
+
+                    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+                        Some((to_isize(self.x)?, to_isize(self.y)?))
+                    }
+
+                    fn position(&self) -> (f64, f64) {
+                        (self.x as f64, self.y as f64)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_euclid_point!(i32, i64, isize);
+}
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use crate::site::{expect_coordinates, to_isize, Point};
+
+    macro_rules! impl_mint_point {
+        ($($ty:ty),*) => {
+            $(
+                impl Point for mint::Point2<$ty> {
+                    fn coordinates(&self) -> (isize, isize) {
+                        expect_coordinates(self)
+                    }
+
+                    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+                        Some((to_isize(self.x)?, to_isize(self.y)?))
+                    }
+
+                    fn position(&self) -> (f64, f64) {
+                        (self.x as f64, self.y as f64)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_mint_point!(i32, i64, isize);
+}
+
+#[cfg(all(test, feature = "mint"))]
+mod tests {
+    use crate::discrete_voronoi::VoronoiBuilder;
+
+    #[test]
+    fn build_from_weighted_mint_points() {
+        let sites = vec![(mint::Point2 { x: 0i32, y: 0 }, 1f32), (mint::Point2 { x: 5, y: 5 }, 2f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        assert!(tess.is_complete());
+        assert_eq!(tess.bounds().dimensions(), (6, 6));
+    }
+}
@@ -1,10 +1,58 @@
-#![feature(nll)]
-
 mod site;
 pub mod metric;
 mod grid;
+mod rasterizer;
 mod discrete_voronoi;
+mod error;
+mod interop;
+pub mod snapshot;
+pub mod rle;
+pub mod compare;
+mod summary;
+mod counters;
+mod diagnostics;
+mod map;
+mod fixed;
+mod propagate;
+pub mod stats;
+pub mod topology;
+pub mod stream;
+pub mod influence;
+pub mod skeleton;
+pub mod transform;
+pub mod stipple;
+pub mod mapgen;
+pub mod district;
+pub mod facility;
+pub mod hierarchy;
+pub mod cluster;
+pub mod wavefront;
+pub mod terrain;
+pub mod morphology;
+pub mod subcell;
+pub mod blend;
+pub mod noise;
+pub mod flow;
+#[cfg(feature = "arrow")]
+pub mod arrow_export;
+#[cfg(feature = "image")]
+pub mod overlay;
+#[cfg(feature = "image")]
+pub mod superpixel;
+#[cfg(feature = "image")]
+pub mod watershed;
+#[cfg(feature = "rand")]
+mod random;
 
 pub use site::*;
-pub use grid::BoundingBox;
-pub use discrete_voronoi::{VoronoiBuilder, VoronoiTesselation};
\ No newline at end of file
+pub use grid::{BoundingBox, Cell, Cells, CellsIter, GridIdx};
+pub use rasterizer::{CellRasterizer, DiscRasterizer, SiteRasterizer};
+pub use discrete_voronoi::{BuildReport, DuplicatePolicy, DuplicateSite, IntoCells, MergeTarget, MergedRegion, OutOfBoundsPolicy, SiteOwner, TieBreak, VoronoiBuilder, VoronoiTesselation, WeightPolicy};
+pub use summary::Summary;
+pub use counters::ComputeCounters;
+pub use diagnostics::{MetricWarning, Mismatch, Verification};
+pub use map::VoronoiMap;
+pub use fixed::FixedGrid;
+pub use error::VoronoiError;
+#[cfg(feature = "rand")]
+pub use random::poisson_disk_sites;
\ No newline at end of file
@@ -0,0 +1,133 @@
+use crate::discrete_voronoi::SiteOwner;
+use crate::grid::{BoundingBox, GridIdx};
+
+use std::sync::Arc;
+
+const NO_OWNER: u32 = u32::MAX;
+
+// Read-only result of a tesselation, keeping only the owner of every cell and
+// the sites indexed by owner id. Clones share the same storage.
+#[derive(Debug)]
+pub struct VoronoiMap<S> {
+    bounds: BoundingBox,
+    // Owner id per cell in row-major order, all ones standing in for no owner
+    owners: Arc<[u32]>,
+    sites: Arc<[S]>
+}
+
+impl<S> VoronoiMap<S> {
+    // Expects the sites to be sorted by owner id
+    pub(crate) fn new<I>(bounds: BoundingBox, owners: I, sites: Vec<S>) -> Self
+    where
+        I: IntoIterator<Item = Option<SiteOwner>>
+    {
+        VoronoiMap {
+            bounds,
+            owners: owners.into_iter().map(|owner| owner.map_or(NO_OWNER, |owner| owner.0)).collect(),
+            sites: sites.into()
+        }
+    }
+
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    // Number of sites in the map
+    pub fn len(&self) -> usize {
+        self.sites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+
+    pub fn site(&self, owner: SiteOwner) -> Option<&S> {
+        self.sites.get(owner.0 as usize)
+    }
+
+    // Sites paired with their owner ids, sorted by id
+    pub fn sites(&self) -> impl Iterator<Item = (SiteOwner, &S)> + '_ {
+        self.sites.iter().enumerate().map(|(id, site)| (SiteOwner(id as u32), site))
+    }
+
+    // Owner of every cell in row-major order
+    pub fn owners(&self) -> impl Iterator<Item = Option<SiteOwner>> + '_ {
+        self.owners.iter().map(|&owner| decode(owner))
+    }
+
+    // Owner of the cell at the coordinates, None if the cell is unowned or
+    // outside of the bounds
+    pub fn owner_at(&self, coordinates: (isize, isize)) -> Option<(SiteOwner, &S)> {
+        let idx = GridIdx::from(coordinates);
+        if !idx.inside(&self.bounds) {
+            return None;
+        }
+
+        let (x, y) = self.bounds.translate_idx(idx);
+        let (width, _) = self.bounds.dimensions();
+        let owner = decode(self.owners[x + y * width])?;
+
+        Some((owner, &self.sites[owner.0 as usize]))
+    }
+
+    // Coordinates of the cells owned by the site in row-major order, empty for
+    // unknown owners
+    pub fn region_of(&self, owner: SiteOwner) -> impl Iterator<Item = GridIdx> + '_ {
+        let (width, _) = self.bounds.dimensions();
+        let (x_offset, y_offset) = self.bounds.offset();
+
+        self.owners
+            .iter()
+            .enumerate()
+            .filter(move |&(_, &cell_owner)| cell_owner == owner.0)
+            .map(move |(idx, _)| GridIdx::from((x_offset + (idx % width) as isize, y_offset + (idx / width) as isize)))
+    }
+}
+
+fn decode(owner: u32) -> Option<SiteOwner> {
+    match owner {
+        NO_OWNER => None,
+        owner => Some(SiteOwner(owner))
+    }
+}
+
+impl<S> Clone for VoronoiMap<S> {
+    fn clone(&self) -> Self {
+        VoronoiMap {
+            bounds: self.bounds,
+            owners: Arc::clone(&self.owners),
+            sites: Arc::clone(&self.sites)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+
+    #[test]
+    fn finalize_computed_voronoi() {
+        let mut tess = VoronoiBuilder::new(vec![(0, 0, 1f32), (5, 0, 1f32)])
+            .bounds(BoundingBox::new(0, 0, 6, 2))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let expected: Vec<Option<SiteOwner>> = tess.cells().iter().map(|(_, cell)| cell.owner()).collect();
+        let map = tess.finalize();
+        let shared = map.clone();
+
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared.owners().collect::<Vec<_>>(), expected);
+        assert_eq!(shared.owner_at((1, 1)), Some((SiteOwner(0), &(0, 0, 1f32))));
+        assert_eq!(shared.owner_at((6, 0)), None);
+        assert_eq!(shared.site(SiteOwner(1)), Some(&(5, 0, 1f32)));
+
+        let region: Vec<GridIdx> = map.region_of(SiteOwner(1)).collect();
+        assert_eq!(region.len(), 6);
+        assert!(region.contains(&GridIdx::from((3, 1))));
+
+        std::thread::spawn(move || assert_eq!(shared.bounds().dimensions(), (6, 2))).join().unwrap();
+    }
+}
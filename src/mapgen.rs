@@ -0,0 +1,199 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+use std::cmp::Reverse;
+
+// What is known about a region when deciding its attributes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region<'a, S> {
+    pub owner: SiteOwner,
+    pub site: &'a S,
+    // Number of cells in the region
+    pub area: usize,
+    // Mean position of the cells, the site position for empty regions
+    pub centroid: (f64, f64),
+    // Whether the region touches the edge of the bounds
+    pub on_border: bool,
+    // Regions sharing an edge with this one, sorted by id
+    pub neighbors: Vec<SiteOwner>
+}
+
+// Collects the area, centroid and neighbours of every region, sorted by id
+pub fn regions<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<Region<'_, S>>
+where
+    S: Site,
+    M: Metric
+{
+    let mut regions: Vec<Region<'_, S>> = tesselation
+        .owners()
+        .map(|owner| Region {
+            owner,
+            site: tesselation.site(owner).expect("owners come from the tesselation"),
+            area: 0,
+            centroid: (0.0, 0.0),
+            on_border: false,
+            neighbors: Vec::new()
+        })
+        .collect();
+
+    let bounds = tesselation.bounds();
+    for (idx, cell) in tesselation.cells() {
+        let owner = match cell.owner() {
+            Some(owner) => owner,
+            None => continue
+        };
+        let (x, y) = idx.coordinates();
+        let region = &mut regions[owner.0 as usize];
+
+        region.area += 1;
+        region.centroid.0 += x as f64;
+        region.centroid.1 += y as f64;
+        if idx.neighbors(bounds).count() < 4 {
+            region.on_border = true;
+        }
+
+        for neighbor in idx.neighbors(bounds) {
+            if let Some(other) = tesselation[neighbor].owner().filter(|&other| other != owner) {
+                region.neighbors.push(other);
+            }
+        }
+    }
+
+    for region in &mut regions {
+        region.centroid = match region.area {
+            0 => region.site.position(),
+            area => (region.centroid.0 / area as f64, region.centroid.1 / area as f64)
+        };
+        region.neighbors.sort_unstable();
+        region.neighbors.dedup();
+    }
+
+    regions
+}
+
+// Gives every region a label from the callback and paints the labels onto a
+// row-major raster of the cells, None for unowned cells. Regions are labelled
+// in id order and the callback sees the labels given so far, indexed by id,
+// so it can take already labelled neighbours into account.
+pub fn label_regions<S, M, T, F>(tesselation: &VoronoiTesselation<S, M>, mut label: F) -> Vec<Option<T>>
+where
+    S: Site,
+    M: Metric,
+    T: Clone,
+    F: FnMut(&Region<'_, S>, &[T]) -> T
+{
+    let mut labels: Vec<T> = Vec::with_capacity(tesselation.len());
+    for region in regions(tesselation) {
+        let value = label(&region, &labels);
+        labels.push(value);
+    }
+
+    tesselation
+        .cells()
+        .iter()
+        .map(|(_, cell)| cell.owner().map(|owner| labels[owner.0 as usize].clone()))
+        .collect()
+}
+
+// Colors the regions so that no two regions sharing an edge get the same
+// color, returning the color index of every region sorted by id. Uses DSATUR,
+// which colors the region with the most differently colored neighbours next,
+// so maps that can be colored with few colors usually are.
+pub fn color_regions<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<(SiteOwner, usize)>
+where
+    S: Site,
+    M: Metric
+{
+    let found = regions(tesselation);
+    let mut colors: Vec<Option<usize>> = vec![None; found.len()];
+
+    for _ in 0..found.len() {
+        // Distinct colors among the neighbours of the region
+        let used = |region: &Region<'_, S>| {
+            let mut used: Vec<usize> = region
+                .neighbors
+                .iter()
+                .filter_map(|neighbor| colors[neighbor.0 as usize])
+                .collect();
+            used.sort_unstable();
+            used.dedup();
+            used
+        };
+
+        // Ties go to the region with the most neighbours, then the lowest id
+        let next = found
+            .iter()
+            .filter(|region| colors[region.owner.0 as usize].is_none())
+            .max_by_key(|region| (used(region).len(), region.neighbors.len(), Reverse(region.owner)))
+            .expect("an uncolored region is left every round");
+
+        let taken = used(next);
+        let color = (0..).find(|color| !taken.contains(color)).expect("some color is always free");
+        colors[next.owner.0 as usize] = Some(color);
+    }
+
+    found
+        .iter()
+        .zip(colors)
+        .map(|(region, color)| (region.owner, color.expect("every region is colored")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Biome {
+        Ocean,
+        Coast,
+        Land
+    }
+
+    #[test]
+    fn label_biomes() {
+        let sites = vec![(0, 0, 1f32), (4, 4, 1f32), (8, 8, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 9, 9)).build().unwrap();
+        tess.compute();
+
+        let found = regions(&tess);
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[1].neighbors, vec![SiteOwner(0), SiteOwner(2)]);
+        assert_eq!(found.iter().map(|region| region.area).sum::<usize>(), 81);
+
+        let raster = label_regions(&tess, |region, labels| {
+            if region.owner == SiteOwner(0) {
+                Biome::Ocean
+            } else if region.neighbors.iter().any(|&neighbor| labels.get(neighbor.0 as usize) == Some(&Biome::Ocean)) {
+                Biome::Coast
+            } else {
+                Biome::Land
+            }
+        });
+
+        assert_eq!(raster[0], Some(Biome::Ocean));
+        assert_eq!(raster[4 + 4 * 9], Some(Biome::Coast));
+        assert_eq!(raster[80], Some(Biome::Land));
+    }
+
+    #[test]
+    fn color_adjacent_regions_differently() {
+        let sites = vec![(0, 0, 1f32), (8, 0, 1f32), (4, 4, 1f32), (0, 8, 1f32), (8, 8, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 9, 9)).build().unwrap();
+        tess.compute();
+
+        let colors = color_regions(&tess);
+        assert_eq!(colors.len(), 5);
+        for region in regions(&tess) {
+            for neighbor in &region.neighbors {
+                assert_ne!(colors[region.owner.0 as usize].1, colors[neighbor.0 as usize].1);
+            }
+        }
+        // The center touches all others, which only touch their neighbours
+        // along the edges
+        assert!(colors.iter().map(|&(_, color)| color).max() <= Some(2));
+    }
+}
@@ -1,6 +1,6 @@
-use site::{Point, Site};
+use crate::site::{Point, Site, Weight};
 
-type OR = f32;
+type OR = f64;
 type IR = f64;
 
 pub trait Metric
@@ -12,6 +12,25 @@ where
     where
         S: Site,
         X: Point;
+
+    // Whether the metric gives meaningful distances for a site with the weight,
+    // checked by the builder according to its weight policy
+    fn valid_weight(_weight: f64) -> bool {
+        true
+    }
+
+    // Whether every region is a single connected piece for any weights, which
+    // growing regions from their sites relies on
+    fn connected_regions() -> bool {
+        true
+    }
+
+    // How far the border between two sites bends away from a straight line
+    // over the given length, in cells. Only borders that bend can cut a
+    // region into pieces, metrics with straight borders keep the default.
+    fn border_bend<S: Site>(_a: &S, _b: &S, _length: f64) -> f64 {
+        0.0
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -23,11 +42,11 @@ impl Euclidean {
         A: Point,
         B: Point
     {
-        let (a_x, a_y) = a.coordinates();
-        let (b_x, b_y) = b.coordinates();
+        let (a_x, a_y) = a.position();
+        let (b_x, b_y) = b.position();
 
-        let mag_x = (a_x as IR - b_x as IR).powi(2);
-        let mag_y = (a_y as IR - b_y as IR).powi(2);
+        let mag_x = (a_x - b_x).powi(2);
+        let mag_y = (a_y - b_y).powi(2);
 
         mag_x + mag_y
     }
@@ -56,7 +75,40 @@ impl Metric for MultWeightedEuclidean {
         S: Site,
         X: Point
     {
-        (1 as OR / a.weight()) * Euclidean::distance(a, b)
+        (1 as OR / a.weight().to_f64()) * Euclidean::distance(a, b)
+    }
+
+    // Zero weights give infinite distances and negative weights turn the
+    // ordering around
+    fn valid_weight(weight: f64) -> bool {
+        weight > 0.0
+    }
+
+    // Regions are bounded by circles, which can cut the regions of the
+    // heavier sites or the bounds into several pieces
+    fn connected_regions() -> bool {
+        false
+    }
+
+    // The border of two sites with different weights is a circle around the
+    // lighter one, whose radius shrinks from infinity as the ratio of the
+    // weights moves away from 1. Over the length the circle strays from its
+    // chord by the height of the arc, or by its whole radius when it fits.
+    fn border_bend<S: Site>(a: &S, b: &S, length: f64) -> f64 {
+        let (a_weight, b_weight) = (a.weight().to_f64(), b.weight().to_f64());
+        let ratio = a_weight.min(b_weight) / a_weight.max(b_weight);
+        let distance = Euclidean::magnitude(a, b).sqrt();
+        if ratio >= 1.0 || distance == 0.0 {
+            return 0.0;
+        }
+
+        let radius = ratio * distance / (1.0 - ratio.powi(2));
+        let half_length = length / 2.0;
+        if radius <= half_length {
+            radius
+        } else {
+            radius - (radius.powi(2) - half_length.powi(2)).sqrt()
+        }
     }
 }
 
@@ -71,7 +123,7 @@ impl Metric for AdditiveWeightedEuclidean {
         S: Site,
         X: Point
     {
-        Euclidean::distance(a, b) - a.weight()
+        Euclidean::distance(a, b) - a.weight().to_f64()
     }
 }
 
@@ -86,7 +138,7 @@ impl Metric for PowerEuclidean {
         S: Site,
         X: Point
     {
-        (Euclidean::magnitude(a, b) - a.weight().powi(2) as IR) as Self::Output
+        (Euclidean::magnitude(a, b) - a.weight().to_f64().powi(2)) as Self::Output
     }
 }
 
@@ -101,13 +153,85 @@ impl Metric for Manhattan {
         S: Site,
         X: Point
     {
-        let (a_x, a_y) = a.coordinates();
-        let (b_x, b_y) = b.coordinates();
+        let (mag_x, mag_y) = offsets(a, b);
+
+        mag_x + mag_y
+    }
+}
+
+// Absolute offsets between two points along each axis
+fn offsets<A, B>(a: &A, b: &B) -> (IR, IR)
+where
+    A: Point,
+    B: Point
+{
+    let (a_x, a_y) = a.position();
+    let (b_x, b_y) = b.position();
+
+    ((a_x - b_x).abs(), (a_y - b_y).abs())
+}
+
+// Number of king moves between the points, diagonal steps cost the same as
+// straight ones
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Chebyshev;
+
+impl Metric for Chebyshev {
+    type Output = OR;
+
+    fn distance<S, X>(a: &S, b: &X) -> Self::Output
+    where
+        S: Site,
+        X: Point
+    {
+        let (mag_x, mag_y) = offsets(a, b);
+
+        mag_x.max(mag_y)
+    }
+}
+
+// 3-4 chamfer distance, straight steps cost 3 and diagonal steps 4, scaled
+// down by 3 so it stays close to the euclidean distance. Worked out directly
+// from the offsets instead of propagating the chamfer mask.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Chamfer;
+
+impl Metric for Chamfer {
+    type Output = OR;
 
-        let mag_x = (a_x as IR - b_x as IR).abs();
-        let mag_y = (a_y as IR - b_y as IR).abs();
-        let magnitude = mag_x + mag_y;
+    fn distance<S, X>(a: &S, b: &X) -> Self::Output
+    where
+        S: Site,
+        X: Point
+    {
+        // 4 * diagonal + 3 * straight steps, with the straight steps being
+        // the longer offset less the diagonal ones
+        let (mag_x, mag_y) = offsets(a, b);
+
+        (mag_x.min(mag_y) + 3.0 * mag_x.max(mag_y)) / 3.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::GridIdx;
+
+    #[test]
+    fn integer_metric_distances() {
+        let site = (0isize, 0isize, 1f32);
+        let point = (3isize, -5isize);
+
+        assert_eq!(Manhattan::distance(&site, &point), 8.0);
+        assert_eq!(Chebyshev::distance(&site, &point), 5.0);
+        assert_eq!(Chamfer::distance(&site, &point), 6.0);
+        assert_eq!(Chamfer::distance(&site, &(6isize, 0isize)), 6.0);
+    }
 
-        magnitude as Self::Output
+    #[test]
+    fn sub_cell_distances() {
+        let offset = (0.25f64, 0.0f64, 1f32);
+        assert_eq!(Manhattan::distance(&offset, &GridIdx::from((2, 1))), 2.75);
+        assert_eq!(Chebyshev::distance(&offset, &GridIdx::from((2, 1))), 1.75);
     }
 }
@@ -0,0 +1,154 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::error::VoronoiError;
+use crate::grid::BoundingBox;
+use crate::metric::Metric;
+use crate::site::Site;
+
+use std::collections::VecDeque;
+
+// Cells owned by the site as a row-major mask over the bounds
+pub fn region_mask<S, M>(tesselation: &VoronoiTesselation<S, M>, owner: SiteOwner) -> Vec<bool>
+where
+    S: Site,
+    M: Metric
+{
+    tesselation.cells().iter().map(|(_, cell)| cell.owner() == Some(owner)).collect()
+}
+
+// Grows the row-major mask by the given number of steps between cells
+// sharing an edge, stopping at the edge of the bounds
+pub fn dilate(mask: &[bool], bounds: &BoundingBox, steps: usize) -> Result<Vec<bool>, VoronoiError> {
+    check_length(mask, bounds)?;
+
+    Ok(grow(mask, bounds, steps))
+}
+
+// Shrinks the row-major mask by the given number of steps between cells
+// sharing an edge. Only cells outside of the mask eat into it, the edge of
+// the bounds does not.
+pub fn erode(mask: &[bool], bounds: &BoundingBox, steps: usize) -> Result<Vec<bool>, VoronoiError> {
+    check_length(mask, bounds)?;
+
+    let outside: Vec<bool> = mask.iter().map(|&inside| !inside).collect();
+    Ok(grow(&outside, bounds, steps).into_iter().map(|outside| !outside).collect())
+}
+
+// Owners of the cells in row-major order with every region eroded by the
+// given number of steps, leaving unowned buffer strips between the regions.
+// Unowned cells eat into the regions around them too.
+pub fn erode_regions<S, M>(tesselation: &VoronoiTesselation<S, M>, steps: usize) -> Vec<Option<SiteOwner>>
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let owners: Vec<Option<SiteOwner>> = tesselation.cells().iter().map(|(_, cell)| cell.owner()).collect();
+    if steps == 0 {
+        return owners;
+    }
+
+    // Steps from every owned cell to the closest cell with another owner,
+    // which is always reached through cells of the same region
+    let mut distance: Vec<Option<usize>> = vec![None; owners.len()];
+    let mut pending = VecDeque::new();
+    for (position, (idx, _)) in tesselation.cells().iter().enumerate() {
+        let on_border = idx.neighbors(bounds).any(|neighbor| tesselation[neighbor].owner() != owners[position]);
+        if owners[position].is_some() && on_border {
+            distance[position] = Some(1);
+            pending.push_back((idx, 1));
+        }
+    }
+
+    while let Some((idx, steps_taken)) = pending.pop_front() {
+        if steps_taken >= steps {
+            continue;
+        }
+        let owner = tesselation[idx].owner();
+        for neighbor in idx.neighbors(bounds) {
+            let position = bounds.linear_idx(neighbor).expect("neighbors are inside of the bounds");
+            if distance[position].is_none() && owners[position] == owner {
+                distance[position] = Some(steps_taken + 1);
+                pending.push_back((neighbor, steps_taken + 1));
+            }
+        }
+    }
+
+    owners
+        .into_iter()
+        .zip(distance)
+        .map(|(owner, distance)| if distance.is_some() { None } else { owner })
+        .collect()
+}
+
+fn check_length(mask: &[bool], bounds: &BoundingBox) -> Result<(), VoronoiError> {
+    bounds.validate()?;
+    let (width, height) = bounds.dimensions();
+    if mask.len() != width * height {
+        return Err(VoronoiError::BufferLength {
+            expected: width * height,
+            actual: mask.len()
+        });
+    }
+
+    Ok(())
+}
+
+// Breadth first growth of the set cells, at most the given number of steps
+fn grow(mask: &[bool], bounds: &BoundingBox, steps: usize) -> Vec<bool> {
+    let mut grown = mask.to_vec();
+    let mut pending: VecDeque<_> = bounds
+        .coordinates_iter()
+        .zip(mask)
+        .filter(|(_, &inside)| inside)
+        .map(|(idx, _)| (idx, 0))
+        .collect();
+
+    while let Some((idx, steps_taken)) = pending.pop_front() {
+        if steps_taken >= steps {
+            continue;
+        }
+        for neighbor in idx.neighbors(bounds) {
+            let position = bounds.linear_idx(neighbor).expect("neighbors are inside of the bounds");
+            if !grown[position] {
+                grown[position] = true;
+                pending.push_back((neighbor, steps_taken + 1));
+            }
+        }
+    }
+
+    grown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+
+    #[test]
+    fn dilate_and_erode_masks() {
+        let bounds = BoundingBox::new(0, 0, 5, 5);
+        let mut mask = vec![false; 25];
+        mask[12] = true;
+
+        let grown = dilate(&mask, &bounds, 2).unwrap();
+        assert_eq!(grown.iter().filter(|&&inside| inside).count(), 13);
+        assert!(grown[2] && !grown[0]);
+
+        assert_eq!(erode(&grown, &bounds, 2).unwrap(), mask);
+        // The edge of the bounds does not erode a full mask
+        assert_eq!(erode(&[true; 25], &bounds, 3).unwrap(), vec![true; 25]);
+        assert_eq!(dilate(&mask[1..], &bounds, 1), Err(VoronoiError::BufferLength { expected: 25, actual: 24 }));
+    }
+
+    #[test]
+    fn buffer_strips_between_regions() {
+        let sites = vec![(0, 0, 1f32), (7, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 8, 1)).build().unwrap();
+        tess.compute();
+
+        let eroded = erode_regions(&tess, 2);
+        let expected = [Some(0), Some(0), None, None, None, None, Some(1), Some(1)];
+        assert_eq!(eroded, expected.map(|owner| owner.map(SiteOwner)));
+        assert_eq!(region_mask(&tess, SiteOwner(1)), vec![false, false, false, false, true, true, true, true]);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::VoronoiError;
+use crate::grid::BoundingBox;
+use crate::metric::Metric;
+use crate::site::Site;
+
+// Which distances of a cell make up the noise value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Feature {
+    // Distance to the closest site, dark spots around the sites
+    #[default]
+    F1,
+    // Distance to the second closest site
+    F2,
+    // Difference between the two, zero along the region borders
+    F2MinusF1
+}
+
+// Cellular noise after Worley over every cell of the bounds in row-major
+// order, using the distances under the metric. With a single site there is
+// no second closest one and F2 is infinite. Every site is measured against
+// every cell.
+pub fn worley_noise<S, M>(sites: &[S], bounds: BoundingBox, feature: Feature) -> Result<Vec<f64>, VoronoiError>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    bounds.validate()?;
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+
+    Ok(bounds
+        .coordinates_iter()
+        .map(|idx| {
+            let (mut first, mut second) = (f64::INFINITY, f64::INFINITY);
+            for site in sites {
+                let distance: f64 = M::distance(site, &idx).into();
+                if distance < first {
+                    second = first;
+                    first = distance;
+                } else if distance < second {
+                    second = distance;
+                }
+            }
+
+            match feature {
+                Feature::F1 => first,
+                Feature::F2 => second,
+                Feature::F2MinusF1 => second - first
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Manhattan;
+
+    #[test]
+    fn cellular_noise_features() {
+        let sites = [(0isize, 0isize), (4, 0)];
+        let bounds = BoundingBox::new(0, 0, 5, 1);
+
+        let noise = |feature| worley_noise::<_, Manhattan>(&sites, bounds, feature).unwrap();
+        assert_eq!(noise(Feature::F1), vec![0.0, 1.0, 2.0, 1.0, 0.0]);
+        assert_eq!(noise(Feature::F2), vec![4.0, 3.0, 2.0, 3.0, 4.0]);
+        assert_eq!(noise(Feature::F2MinusF1), vec![4.0, 2.0, 0.0, 2.0, 4.0]);
+
+        assert_eq!(worley_noise::<_, Manhattan>(&sites[..1], bounds, Feature::F2).unwrap()[0], f64::INFINITY);
+        assert_eq!(worley_noise::<(isize, isize), Manhattan>(&[], bounds, Feature::F1), Err(VoronoiError::NoSites));
+    }
+}
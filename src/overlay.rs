@@ -0,0 +1,132 @@
+use crate::discrete_voronoi::VoronoiTesselation;
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+use image::{Rgba, RgbaImage};
+
+// Styling for drawing region boundaries on top of an image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlayStyle {
+    boundary_color: Rgba<u8>,
+    thickness: u32,
+    site_marker: Option<(Rgba<u8>, u32)>
+}
+
+impl OverlayStyle {
+    pub fn new(boundary_color: Rgba<u8>) -> Self {
+        OverlayStyle {
+            boundary_color,
+            thickness: 1,
+            site_marker: None
+        }
+    }
+
+    // Width in pixels of the drawn boundary lines, at least 1
+    pub fn thickness(mut self, thickness: u32) -> Self {
+        self.thickness = thickness.max(1);
+
+        self
+    }
+
+    // Draw a square marker of the given radius centered on every site
+    pub fn site_markers(mut self, color: Rgba<u8>, radius: u32) -> Self {
+        self.site_marker = Some((color, radius));
+
+        self
+    }
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        OverlayStyle::new(Rgba([0, 0, 0, 255]))
+    }
+}
+
+// Draws the boundaries of every region onto the image. The image pixel (0, 0)
+// corresponds to the top left corner of the tesselation bounds, anything
+// falling outside of the image is skipped. Of the two cells along an edge
+// between regions only the later one in row-major order is drawn, so lines
+// are as wide as the thickness.
+pub fn draw_boundaries<S, M>(tesselation: &VoronoiTesselation<S, M>, image: &mut RgbaImage, style: &OverlayStyle)
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let (x_offset, y_offset) = bounds.offset();
+    let spread = (style.thickness as isize - 1) / 2;
+
+    let (width, _) = bounds.dimensions();
+    let cells = tesselation.cells();
+
+    for (position, (idx, cell)) in cells.iter().enumerate() {
+        let on_boundary = idx.neighbors(bounds).any(|neighbor| {
+            let (x, y) = bounds.translate_idx(neighbor);
+            let neighbor_position = x + y * width;
+            neighbor_position < position && cells[neighbor_position].owner() != cell.owner()
+        });
+
+        if on_boundary {
+            let (x, y) = idx.coordinates();
+            let corner = (x - x_offset - spread, y - y_offset - spread);
+            fill_square(image, corner, style.thickness as isize, style.boundary_color);
+        }
+    }
+
+    if let Some((color, radius)) = style.site_marker {
+        for (_, site) in tesselation.owned_sites() {
+            let (x, y) = site.coordinates();
+            let corner = (x - x_offset - radius as isize, y - y_offset - radius as isize);
+            fill_square(image, corner, 2 * radius as isize + 1, color);
+        }
+    }
+}
+
+// Fill a square of pixels, clipped to the image, starting at the given corner
+fn fill_square(image: &mut RgbaImage, corner: (isize, isize), size: isize, color: Rgba<u8>) {
+    let (corner_x, corner_y) = corner;
+    let (width, height) = (image.width() as isize, image.height() as isize);
+
+    for y in corner_y.max(0)..(corner_y + size).min(height) {
+        for x in corner_x.max(0)..(corner_x + size).min(width) {
+            image.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+
+    #[test]
+    fn draw_boundaries_between_two_sites() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (5, 0, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites)
+            .bounds(BoundingBox::new(0, 0, 6, 2))
+            .build().unwrap();
+        tess.compute();
+
+        let background = Rgba([255, 255, 255, 255]);
+        let mut image = RgbaImage::from_pixel(6, 2, background);
+        draw_boundaries(&tess, &mut image, &OverlayStyle::default());
+
+        assert_eq!(*image.get_pixel(0, 0), background);
+        assert_eq!(*image.get_pixel(5, 1), background);
+        assert!(image.pixels().any(|pixel| *pixel != background));
+
+        // The regions meet between columns 2 and 3, a line one pixel wide
+        // only takes the later column
+        for y in 0..2 {
+            let drawn: Vec<u32> = (0..6).filter(|&x| *image.get_pixel(x, y) != background).collect();
+            assert_eq!(drawn, vec![3]);
+        }
+
+        let mut image = RgbaImage::from_pixel(6, 2, background);
+        draw_boundaries(&tess, &mut image, &OverlayStyle::default().thickness(3));
+        let drawn: Vec<u32> = (0..6).filter(|&x| *image.get_pixel(x, 0) != background).collect();
+        assert_eq!(drawn, vec![2, 3, 4]);
+    }
+}
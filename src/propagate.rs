@@ -0,0 +1,108 @@
+use crate::grid::{BoundingBox, GridIdx};
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// Outcome of spreading sources over the cells by priority, in row-major order.
+// Cells no source reached have an infinite priority and no source.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Propagation {
+    pub(crate) priority: Vec<f64>,
+    pub(crate) source: Vec<Option<usize>>,
+    // Position of the cell every cell was reached from, None for the seeds
+    pub(crate) parent: Vec<Option<usize>>
+}
+
+// A pending claim of a source on a cell, ordered so the heap pops the lowest
+// priority first and settles equal priorities by the lower source
+struct Claim {
+    priority: f64,
+    source: usize,
+    position: usize,
+    idx: GridIdx,
+    parent: Option<usize>
+}
+
+impl PartialEq for Claim {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Claim {}
+
+impl PartialOrd for Claim {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Claim {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.source.cmp(&self.source))
+            .then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+// Settles the cells one at a time in order of priority, starting from the
+// seeds given as cell, source and priority. Settling a cell offers its
+// unsettled neighbours to the same source at the priority returned by the
+// closure, which gets the source, the settled cell with its priority and the
+// neighbour, or None to keep the source out of the neighbour. Seeds outside
+// of the bounds are ignored.
+pub(crate) fn propagate<I, F>(bounds: &BoundingBox, seeds: I, mut priority: F) -> Propagation
+where
+    I: IntoIterator<Item = (GridIdx, usize, f64)>,
+    F: FnMut(usize, GridIdx, f64, GridIdx) -> Option<f64>
+{
+    let (width, height) = bounds.dimensions();
+    let mut result = Propagation {
+        priority: vec![f64::INFINITY; width * height],
+        source: vec![None; width * height],
+        parent: vec![None; width * height]
+    };
+
+    let mut heap = BinaryHeap::new();
+    for (idx, source, seed_priority) in seeds {
+        if let Some(position) = bounds.linear_idx(idx) {
+            heap.push(Claim {
+                priority: seed_priority,
+                source,
+                position,
+                idx,
+                parent: None
+            });
+        }
+    }
+
+    while let Some(claim) = heap.pop() {
+        if result.source[claim.position].is_some() {
+            continue;
+        }
+        result.priority[claim.position] = claim.priority;
+        result.source[claim.position] = Some(claim.source);
+        result.parent[claim.position] = claim.parent;
+
+        for neighbor in claim.idx.neighbors(bounds) {
+            let position = bounds.linear_idx(neighbor).expect("neighbors are inside of the bounds");
+            if result.source[position].is_some() {
+                continue;
+            }
+
+            if let Some(next) = priority(claim.source, claim.idx, claim.priority, neighbor) {
+                heap.push(Claim {
+                    priority: next,
+                    source: claim.source,
+                    position,
+                    idx: neighbor,
+                    parent: Some(claim.position)
+                });
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,245 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiBuilder, VoronoiTesselation};
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::{Euclidean, Metric};
+use crate::site::Site;
+
+use rand::distr::Distribution;
+use rand::Rng;
+
+type RandomSite = (isize, isize, f32);
+
+impl VoronoiBuilder<RandomSite, Euclidean> {
+    // Places n sites uniformly at random inside the bounds, all with a weight
+    // of 1. Sites landing on the same cell are handled by the duplicate policy,
+    // so the tesselation can end up with fewer than n sites.
+    pub fn with_random_sites<R: Rng>(n: usize, bounds: &BoundingBox, rng: &mut R) -> Self {
+        VoronoiBuilder::new(random_sites(n, bounds, rng, |_| 1.0)).bounds(*bounds)
+    }
+
+    // Places well spaced sites with a weight of 1 inside the bounds, see
+    // poisson_disk_sites
+    pub fn with_poisson_disk_sites<R: Rng>(bounds: &BoundingBox, min_distance: f64, rng: &mut R) -> Self {
+        let sites = poisson_disk_sites(bounds, min_distance, rng).into_iter().map(|(x, y)| (x, y, 1.0));
+
+        VoronoiBuilder::new(sites).bounds(*bounds)
+    }
+
+    // Like with_random_sites, but drawing every weight from the distribution
+    pub fn with_random_weighted_sites<R, D>(n: usize, bounds: &BoundingBox, rng: &mut R, weights: &D) -> Self
+    where
+        R: Rng,
+        D: Distribution<f32>
+    {
+        VoronoiBuilder::new(random_sites(n, bounds, rng, |rng| weights.sample(rng))).bounds(*bounds)
+    }
+}
+
+impl<S, M> VoronoiTesselation<S, M>
+where
+    S: Site,
+    M: Metric
+{
+    // Picks n distinct cells of the region uniformly at random, or all of its
+    // cells in random order if it has fewer. Walks the region once with
+    // reservoir sampling instead of collecting every cell first.
+    pub fn sample_in_region<R: Rng>(&self, owner: SiteOwner, n: usize, rng: &mut R) -> Vec<GridIdx> {
+        let mut sample: Vec<GridIdx> = Vec::with_capacity(n);
+
+        for (seen, (idx, _)) in self.region_of(owner).enumerate() {
+            if seen < n {
+                sample.push(idx);
+            } else {
+                let slot = rng.random_range(0..=seen);
+                if slot < n {
+                    sample[slot] = idx;
+                }
+            }
+        }
+
+        // Regions smaller than the sample come out in row-major order otherwise
+        for last in (1..sample.len()).rev() {
+            sample.swap(last, rng.random_range(0..=last));
+        }
+
+        sample
+    }
+}
+
+// Bridson's algorithm restricted to cell coordinates, every returned cell is
+// at least min_distance away from all of the others and no more cells can be
+// added without breaking that
+pub fn poisson_disk_sites<R: Rng>(bounds: &BoundingBox, min_distance: f64, rng: &mut R) -> Vec<(isize, isize)> {
+    const ATTEMPTS: usize = 30;
+
+    let (x_offset, y_offset) = bounds.offset();
+    let (width, height) = bounds.dimensions();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let min_distance = min_distance.max(1.0);
+    // At most one sample fits in every background cell of this size
+    let cell_size = min_distance / 2f64.sqrt();
+    let background_width = (width as f64 / cell_size).ceil() as usize;
+    let background_height = (height as f64 / cell_size).ceil() as usize;
+    let mut background: Vec<Option<usize>> = vec![None; background_width * background_height];
+    let background_idx = |(x, y): (isize, isize)| {
+        let column = ((x - x_offset) as f64 / cell_size) as usize;
+        let row = ((y - y_offset) as f64 / cell_size) as usize;
+        (column, row)
+    };
+
+    let mut samples: Vec<(isize, isize)> = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+
+    let first = (
+        x_offset + rng.random_range(0..width) as isize,
+        y_offset + rng.random_range(0..height) as isize
+    );
+    let (column, row) = background_idx(first);
+    background[column + row * background_width] = Some(0);
+    samples.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let active_idx = rng.random_range(0..active.len());
+        let (origin_x, origin_y) = samples[active[active_idx]];
+
+        let mut found = false;
+        for _ in 0..ATTEMPTS {
+            let radius = rng.random_range(min_distance..2.0 * min_distance);
+            let angle = rng.random_range(0.0..std::f64::consts::TAU);
+            let candidate = (
+                (origin_x as f64 + radius * angle.cos()).round() as isize,
+                (origin_y as f64 + radius * angle.sin()).round() as isize
+            );
+
+            let inside = x_offset <= candidate.0
+                && candidate.0 < x_offset + width as isize
+                && y_offset <= candidate.1
+                && candidate.1 < y_offset + height as isize;
+            if !inside {
+                continue;
+            }
+
+            let (column, row) = background_idx(candidate);
+            let far_enough = (row.saturating_sub(2)..(row + 3).min(background_height)).all(|neighbor_row| {
+                (column.saturating_sub(2)..(column + 3).min(background_width)).all(|neighbor_column| {
+                    match background[neighbor_column + neighbor_row * background_width] {
+                        Some(sample) => {
+                            let (x, y) = samples[sample];
+                            let (dx, dy) = ((x - candidate.0) as f64, (y - candidate.1) as f64);
+                            (dx * dx + dy * dy).sqrt() >= min_distance
+                        },
+                        None => true
+                    }
+                })
+            });
+
+            if far_enough {
+                background[column + row * background_width] = Some(samples.len());
+                active.push(samples.len());
+                samples.push(candidate);
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            active.swap_remove(active_idx);
+        }
+    }
+
+    samples
+}
+
+fn random_sites<R, F>(n: usize, bounds: &BoundingBox, rng: &mut R, mut weight: F) -> Vec<RandomSite>
+where
+    R: Rng,
+    F: FnMut(&mut R) -> f32
+{
+    let (x_offset, y_offset) = bounds.offset();
+    let (width, height) = bounds.dimensions();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    (0..n)
+        .map(|_| {
+            let x = x_offset + rng.random_range(0..width) as isize;
+            let y = y_offset + rng.random_range(0..height) as isize;
+            (x, y, weight(rng))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::distr::Uniform;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn random_sites_inside_bounds() {
+        let bounds = BoundingBox::new(-5, 10, 20, 8);
+        let mut rng = SmallRng::seed_from_u64(7);
+
+        let mut tess = VoronoiBuilder::with_random_sites(12, &bounds, &mut rng).build().unwrap();
+        tess.compute();
+
+        assert_eq!(tess.bounds(), &bounds);
+        assert!(tess.len() <= 12);
+        assert!(tess.sites().all(|site| GridIdx::from((site.0, site.1)).inside(&bounds) && site.2 == 1.0));
+    }
+
+    #[test]
+    fn random_weights_from_distribution() {
+        let bounds = BoundingBox::new(0, 0, 50, 50);
+        let mut rng = SmallRng::seed_from_u64(7);
+        let weights = Uniform::new(2f32, 4f32).unwrap();
+
+        let tess = VoronoiBuilder::with_random_weighted_sites(10, &bounds, &mut rng, &weights).build().unwrap();
+
+        assert!(tess.sites().all(|site| 2.0 <= site.2 && site.2 < 4.0));
+    }
+
+    #[test]
+    fn poisson_disk_sites_are_separated() {
+        let bounds = BoundingBox::new(3, -4, 60, 40);
+        let mut rng = SmallRng::seed_from_u64(11);
+
+        let sites = poisson_disk_sites(&bounds, 6.0, &mut rng);
+
+        assert!(sites.len() > 10);
+        for (idx, &(x, y)) in sites.iter().enumerate() {
+            assert!(GridIdx::from((x, y)).inside(&bounds));
+            for &(other_x, other_y) in &sites[idx + 1..] {
+                let distance = (((x - other_x).pow(2) + (y - other_y).pow(2)) as f64).sqrt();
+                assert!(distance >= 6.0);
+            }
+        }
+
+        let tess = VoronoiBuilder::with_poisson_disk_sites(&bounds, 6.0, &mut rng).build().unwrap();
+        assert!(tess.duplicate_sites().is_empty());
+    }
+
+    #[test]
+    fn sample_cells_of_region() {
+        let sites = vec![(0, 0, 1f32), (9, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 10, 4)).build().unwrap();
+        tess.compute();
+        let mut rng = SmallRng::seed_from_u64(3);
+
+        let mut sample = tess.sample_in_region(SiteOwner(1), 6, &mut rng);
+        assert_eq!(sample.len(), 6);
+        assert!(sample.iter().all(|&idx| tess[idx].owner() == Some(SiteOwner(1))));
+        sample.sort_unstable();
+        sample.dedup();
+        assert_eq!(sample.len(), 6);
+
+        assert_eq!(tess.sample_in_region(SiteOwner(0), 100, &mut rng).len(), 20);
+        assert!(tess.sample_in_region(SiteOwner(5), 3, &mut rng).is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::grid::GridIdx;
+use crate::site::Site;
+
+use std::fmt;
+
+// Decides which cells a site seeds when the tesselation is built, keeping what
+// a site is apart from how it lands on the grid. Metrics keep measuring from
+// the position of the site, so an extended generator is expected to seed the
+// cells around it.
+pub trait SiteRasterizer<S>: fmt::Debug
+where
+    S: Site
+{
+    // Cells claimed by the site before growing, the ones outside of the bounds
+    // are skipped
+    fn seed_cells(&self, site: &S) -> Vec<GridIdx>;
+}
+
+// Seeds the single cell at the coordinates of the site, the default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellRasterizer;
+
+impl<S> SiteRasterizer<S> for CellRasterizer
+where
+    S: Site
+{
+    fn seed_cells(&self, site: &S) -> Vec<GridIdx> {
+        vec![GridIdx::from(site.coordinates())]
+    }
+}
+
+// Seeds every cell whose center lies within the radius of the position of the
+// site, always including the cell at its coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiscRasterizer {
+    pub radius: f64
+}
+
+impl DiscRasterizer {
+    pub fn new(radius: f64) -> Self {
+        DiscRasterizer { radius }
+    }
+}
+
+impl<S> SiteRasterizer<S> for DiscRasterizer
+where
+    S: Site
+{
+    fn seed_cells(&self, site: &S) -> Vec<GridIdx> {
+        let (x, y) = site.position();
+        let center = site.coordinates();
+        let reach = self.radius.max(0.0).ceil() as isize + 1;
+
+        let mut cells = Vec::new();
+        for cell_y in center.1 - reach..=center.1 + reach {
+            for cell_x in center.0 - reach..=center.0 + reach {
+                let distance = ((cell_x as f64 - x).powi(2) + (cell_y as f64 - y).powi(2)).sqrt();
+                if distance <= self.radius || (cell_x, cell_y) == center {
+                    cells.push(GridIdx::from((cell_x, cell_y)));
+                }
+            }
+        }
+
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::{SiteOwner, VoronoiBuilder};
+    use crate::grid::BoundingBox;
+
+    #[test]
+    fn seed_discs() {
+        let seeds = DiscRasterizer::new(1.0).seed_cells(&(0isize, 0isize, 1f32));
+        assert_eq!(seeds.len(), 5);
+
+        let mut tess = VoronoiBuilder::new(vec![(2, 2, 1f32), (9, 2, 1f32), (-3, 2, 1f32)])
+            .bounds(BoundingBox::new(0, 0, 12, 5))
+            .rasterizer(DiscRasterizer::new(2.0))
+            .build()
+            .unwrap();
+
+        assert_eq!(tess.len(), 2);
+        assert_eq!(tess.build_report().clipped, vec![2]);
+        assert_eq!(tess.cells().iter().filter(|&(idx, _)| tess.claim_step(idx) == Some(0)).count(), 26);
+
+        tess.compute();
+        assert_eq!(tess.owner_at((0, 0)).unwrap().0, SiteOwner(0));
+        assert_eq!(tess.owner_at((11, 4)).unwrap().0, SiteOwner(1));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::metric::Metric;
+use crate::site::Site;
+
+use std::io::{self, Read, Write};
+
+const NO_OWNER: u32 = u32::MAX;
+
+// Most rows or runs reserved up front, counts read back are not trusted
+const MAX_PREALLOCATED: usize = 1 << 16;
+
+// Run of consecutive cells in a row sharing the same owner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub owner: Option<SiteOwner>,
+    pub length: u32
+}
+
+// Label map of a tesselation stored as runs of owners per row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunLengthLabels {
+    width: usize,
+    rows: Vec<Vec<Run>>
+}
+
+impl RunLengthLabels {
+    pub fn encode<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Self
+    where
+        S: Site,
+        M: Metric
+    {
+        let (width, _) = tesselation.bounds().dimensions();
+        let rows = tesselation
+            .cells()
+            .as_slice()
+            .chunks(width.max(1))
+            .map(|row| {
+                let mut runs: Vec<Run> = Vec::new();
+                for cell in row {
+                    match runs.last_mut() {
+                        Some(ref mut run) if run.owner == cell.owner() && run.length < u32::MAX => run.length += 1,
+                        _ => runs.push(Run {
+                            owner: cell.owner(),
+                            length: 1
+                        })
+                    }
+                }
+
+                runs
+            })
+            .collect();
+
+        RunLengthLabels { width, rows }
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.rows.len())
+    }
+
+    pub fn rows(&self) -> &[Vec<Run>] {
+        &self.rows
+    }
+
+    // Expands the runs back into one owner per cell in row-major order
+    pub fn decode(&self) -> Vec<Option<SiteOwner>> {
+        let mut labels = Vec::with_capacity(self.width.checked_mul(self.rows.len()).unwrap_or(0));
+        for run in self.rows.iter().flat_map(|row| row.iter()) {
+            labels.extend((0..run.length).map(|_| run.owner));
+        }
+
+        labels
+    }
+
+    // Layout (little endian): width u32, height u32, then per row the number
+    // of runs as u32 followed by (owner u32, length u32) pairs, all ones
+    // standing in for no owner. Fails for labels too large for the u32 fields.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&to_u32(self.width, "width")?.to_le_bytes())?;
+        writer.write_all(&to_u32(self.rows.len(), "height")?.to_le_bytes())?;
+
+        for row in &self.rows {
+            writer.write_all(&to_u32(row.len(), "number of runs")?.to_le_bytes())?;
+            for run in row {
+                let owner = run.owner.map_or(NO_OWNER, |owner| owner.0);
+                writer.write_all(&owner.to_le_bytes())?;
+                writer.write_all(&run.length.to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let width = to_usize(read_u32(reader)?)?;
+        let height = to_usize(read_u32(reader)?)?;
+        width.checked_mul(height).ok_or_else(|| invalid_data("labels are too large"))?;
+
+        let mut rows = Vec::with_capacity(height.min(MAX_PREALLOCATED));
+        for _ in 0..height {
+            // Every run covers at least one cell
+            let num_runs = to_usize(read_u32(reader)?)?;
+            if num_runs > width {
+                return Err(invalid_data("more runs than cells in a row"));
+            }
+
+            let mut row = Vec::with_capacity(num_runs.min(MAX_PREALLOCATED));
+            let mut row_length: usize = 0;
+            for _ in 0..num_runs {
+                let owner = match read_u32(reader)? {
+                    NO_OWNER => None,
+                    owner => Some(SiteOwner(owner))
+                };
+                let length = read_u32(reader)?;
+                if length == 0 {
+                    return Err(invalid_data("empty run"));
+                }
+                row_length = row_length
+                    .checked_add(to_usize(length)?)
+                    .ok_or_else(|| invalid_data("row length does not match width"))?;
+                row.push(Run { owner, length });
+            }
+
+            if row_length != width {
+                return Err(invalid_data("row length does not match width"));
+            }
+            rows.push(row);
+        }
+
+        Ok(RunLengthLabels { width, rows })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn to_u32(value: usize, field: &str) -> io::Result<u32> {
+    u32::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("{} does not fit in u32", field)))
+}
+
+fn to_usize(value: u32) -> io::Result<usize> {
+    usize::try_from(value).map_err(|_| invalid_data("labels are too large"))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+
+    #[test]
+    fn encode_round_trip() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (9, 3, 1f32), (2, 6, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        let encoded = RunLengthLabels::encode(&tess);
+        let labels: Vec<Option<SiteOwner>> = tess.cells().iter().map(|(_, cell)| cell.owner()).collect();
+        assert_eq!(encoded.dimensions(), tess.bounds().dimensions());
+        assert_eq!(encoded.decode(), labels);
+
+        let mut buffer = Vec::new();
+        encoded.write(&mut buffer).unwrap();
+        assert_eq!(RunLengthLabels::read(&mut buffer.as_slice()).unwrap(), encoded);
+    }
+
+    #[test]
+    fn reject_malformed_runs() {
+        let read_kind = |words: &[u32]| {
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+            RunLengthLabels::read(&mut bytes.as_slice()).unwrap_err().kind()
+        };
+
+        // Counts far beyond the data that follows are not allocated up front
+        assert_eq!(read_kind(&[u32::MAX, u32::MAX]), io::ErrorKind::UnexpectedEof);
+        assert_eq!(read_kind(&[u32::MAX, 1, u32::MAX - 1]), io::ErrorKind::UnexpectedEof);
+        assert_eq!(read_kind(&[4, 1, 5]), io::ErrorKind::InvalidData);
+        assert_eq!(read_kind(&[4, 1, 2, 0, 4, 1, 0]), io::ErrorKind::InvalidData);
+        assert_eq!(read_kind(&[4, 1, 2, 0, u32::MAX, 1, 3]), io::ErrorKind::InvalidData);
+
+        let wide = RunLengthLabels {
+            width: u32::MAX as usize + 1,
+            rows: Vec::new()
+        };
+        assert_eq!(wide.write(&mut Vec::new()).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -1,43 +1,337 @@
 pub trait Point {
+    // Cell containing the point
     fn coordinates(&self) -> (isize, isize);
+
+    // The coordinates if they fit in isize on this platform, which wider
+    // coordinate types can miss. The builder rejects sites without them, their
+    // coordinates panic.
+    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+        Some(self.coordinates())
+    }
+
+    // True position of the point, metrics measure from here. Defaults to the
+    // center of the cell, points with sub-cell positions should override it and
+    // use rasterize to derive their coordinates.
+    fn position(&self) -> (f64, f64) {
+        let (x, y) = self.coordinates();
+        (x as f64, y as f64)
+    }
+}
+
+// Coordinate converted without wrapping, for point types wider than isize on
+// some platforms
+pub(crate) fn to_isize<T: TryInto<isize>>(value: T) -> Option<isize> {
+    value.try_into().ok()
+}
+
+// Coordinates of points with wider coordinate types, see
+// Point::checked_coordinates. Their positions are converted directly, so the
+// builder can look at them before the coordinates.
+pub(crate) fn expect_coordinates<P: Point + ?Sized>(point: &P) -> (isize, isize) {
+    point.checked_coordinates().expect("coordinates do not fit in isize")
+}
+
+// Rasterization rule for sub-cell positions, cell centers sit on integer
+// coordinates and a position belongs to the cell whose center is nearest,
+// rounding halfway cases up
+pub fn rasterize(position: (f64, f64)) -> (isize, isize) {
+    ((position.0 + 0.5).floor() as isize, (position.1 + 0.5).floor() as isize)
 }
 
+// Rasterizes the position if it is finite and its cell fits in isize, where
+// rasterize would saturate
+fn checked_rasterize(position: (f64, f64)) -> Option<(isize, isize)> {
+    let axis = |value: f64| {
+        let cell = (value + 0.5).floor();
+        // isize::MAX rounds up to a power of two as a float, so it is excluded
+        (cell >= isize::MIN as f64 && cell < isize::MAX as f64).then_some(cell as isize)
+    };
+
+    Some((axis(position.0)?, axis(position.1)?))
+}
+
+// Numeric value usable as the weight of a site, metrics work on the value
+// converted to f64
+pub trait Weight: Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_weight {
+    ($($ty:ty),*) => {
+        $(
+            impl Weight for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_weight!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
 pub trait Site: Point {
-    fn weight(&self) -> f32;
+    type Weight: Weight;
+
+    fn weight(&self) -> Self::Weight;
+}
+
+// Site whose weight can be changed in place, see
+// VoronoiTesselation::set_weight
+pub trait SiteMut: Site {
+    fn set_weight(&mut self, weight: Self::Weight);
 }
 
-impl Point for (isize, isize, f32) {
+// Pairs any point with a weight, for point types that do not carry one
+impl<P: Point, W: Weight> Point for (P, W) {
     fn coordinates(&self) -> (isize, isize) {
-        (self.0, self.1)
+        self.0.coordinates()
     }
-}
 
-impl Site for (isize, isize, f32) {
-    fn weight(&self) -> f32 {
-        self.2
+    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+        self.0.checked_coordinates()
+    }
+
+    fn position(&self) -> (f64, f64) {
+        self.0.position()
     }
 }
 
-impl Point for [isize; 3] {
-    fn coordinates(&self) -> (isize, isize) {
-        (self[0], self[1])
+impl<P: Point, W: Weight> Site for (P, W) {
+    type Weight = W;
+
+    fn weight(&self) -> W {
+        self.1
     }
 }
 
-impl Site for [isize; 3] {
-    fn weight(&self) -> f32 {
-        self[2] as f32
+impl<P: Point, W: Weight> SiteMut for (P, W) {
+    fn set_weight(&mut self, weight: W) {
+        self.1 = weight;
     }
 }
 
+// Sites made of plain integer coordinates, with the weight either as a third
+// element or fixed to 1.0. The grid itself always works on isize coordinates,
+// the builder rejects wider ones that do not fit.
+macro_rules! impl_integer_sites {
+    ($($ty:ty),*) => {
+        $(
+            impl<W: Weight> Point for ($ty, $ty, W) {
+                fn coordinates(&self) -> (isize, isize) {
+                    expect_coordinates(self)
+                }
+
+                fn checked_coordinates(&self) -> Option<(isize, isize)> {
+                    Some((to_isize(self.0)?, to_isize(self.1)?))
+                }
+
+                fn position(&self) -> (f64, f64) {
+                    (self.0 as f64, self.1 as f64)
+                }
+            }
+
+            impl<W: Weight> Site for ($ty, $ty, W) {
+                type Weight = W;
+
+                fn weight(&self) -> W {
+                    self.2
+                }
+            }
+
+            impl<W: Weight> SiteMut for ($ty, $ty, W) {
+                fn set_weight(&mut self, weight: W) {
+                    self.2 = weight;
+                }
+            }
+
+            impl Point for [$ty; 3] {
+                fn coordinates(&self) -> (isize, isize) {
+                    expect_coordinates(self)
+                }
+
+                fn checked_coordinates(&self) -> Option<(isize, isize)> {
+                    Some((to_isize(self[0])?, to_isize(self[1])?))
+                }
+
+                fn position(&self) -> (f64, f64) {
+                    (self[0] as f64, self[1] as f64)
+                }
+            }
+
+            impl Site for [$ty; 3] {
+                type Weight = $ty;
+
+                fn weight(&self) -> $ty {
+                    self[2]
+                }
+            }
+
+            impl SiteMut for [$ty; 3] {
+                fn set_weight(&mut self, weight: $ty) {
+                    self[2] = weight;
+                }
+            }
+
+            impl Point for ($ty, $ty) {
+                fn coordinates(&self) -> (isize, isize) {
+                    expect_coordinates(self)
+                }
+
+                fn checked_coordinates(&self) -> Option<(isize, isize)> {
+                    Some((to_isize(self.0)?, to_isize(self.1)?))
+                }
+
+                fn position(&self) -> (f64, f64) {
+                    (self.0 as f64, self.1 as f64)
+                }
+            }
+
+            impl Site for ($ty, $ty) {
+                type Weight = f32;
+
+                fn weight(&self) -> f32 {
+                    1.0
+                }
+            }
+
+            impl Point for [$ty; 2] {
+                fn coordinates(&self) -> (isize, isize) {
+                    expect_coordinates(self)
+                }
+
+                fn checked_coordinates(&self) -> Option<(isize, isize)> {
+                    Some((to_isize(self[0])?, to_isize(self[1])?))
+                }
+
+                fn position(&self) -> (f64, f64) {
+                    (self[0] as f64, self[1] as f64)
+                }
+            }
+
+            impl Site for [$ty; 2] {
+                type Weight = f32;
+
+                fn weight(&self) -> f32 {
+                    1.0
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_sites!(isize, i32, i64);
+
 impl Point for [usize; 3] {
     fn coordinates(&self) -> (isize, isize) {
-        (self[0] as isize, self[1] as isize)
+        expect_coordinates(self)
+    }
+
+    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+        Some((to_isize(self[0])?, to_isize(self[1])?))
+    }
+
+    fn position(&self) -> (f64, f64) {
+        (self[0] as f64, self[1] as f64)
     }
 }
 
 impl Site for [usize; 3] {
+    type Weight = usize;
+
+    fn weight(&self) -> usize {
+        self[2]
+    }
+}
+
+impl SiteMut for [usize; 3] {
+    fn set_weight(&mut self, weight: usize) {
+        self[2] = weight;
+    }
+}
+
+impl Point for (f64, f64) {
+    fn coordinates(&self) -> (isize, isize) {
+        rasterize(*self)
+    }
+
+    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+        checked_rasterize(*self)
+    }
+
+    fn position(&self) -> (f64, f64) {
+        *self
+    }
+}
+
+impl Site for (f64, f64) {
+    type Weight = f32;
+
     fn weight(&self) -> f32 {
-        self[2] as f32
+        1.0
+    }
+}
+
+impl<W: Weight> Point for (f64, f64, W) {
+    fn coordinates(&self) -> (isize, isize) {
+        rasterize((self.0, self.1))
+    }
+
+    fn checked_coordinates(&self) -> Option<(isize, isize)> {
+        checked_rasterize((self.0, self.1))
+    }
+
+    fn position(&self) -> (f64, f64) {
+        (self.0, self.1)
+    }
+}
+
+impl<W: Weight> Site for (f64, f64, W) {
+    type Weight = W;
+
+    fn weight(&self) -> W {
+        self.2
+    }
+}
+
+impl<W: Weight> SiteMut for (f64, f64, W) {
+    fn set_weight(&mut self, weight: W) {
+        self.2 = weight;
+    }
+}
+
+// Site carrying arbitrary user data along with its position and weight, the
+// data is available from every API that hands back sites
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedSite<T, W = f32> {
+    pub x: isize,
+    pub y: isize,
+    pub weight: W,
+    pub data: T
+}
+
+impl<T, W> WeightedSite<T, W> {
+    pub fn new(x: isize, y: isize, weight: W, data: T) -> Self {
+        WeightedSite { x, y, weight, data }
+    }
+}
+
+impl<T, W> Point for WeightedSite<T, W> {
+    fn coordinates(&self) -> (isize, isize) {
+        (self.x, self.y)
+    }
+}
+
+impl<T, W: Weight> Site for WeightedSite<T, W> {
+    type Weight = W;
+
+    fn weight(&self) -> W {
+        self.weight
+    }
+}
+
+impl<T, W: Weight> SiteMut for WeightedSite<T, W> {
+    fn set_weight(&mut self, weight: W) {
+        self.weight = weight;
     }
 }
@@ -0,0 +1,230 @@
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::propagate::propagate;
+use crate::transform::distance_transform;
+
+// Discrete medial axis of the free space between the blocked cells, as a
+// row-major raster with true on the skeleton. A free cell is on the axis when
+// its distance to the closest blocked cell is a ridge along a row or a
+// column, larger than the cell before it and at least as large as the cell
+// after it, which keeps ridges one cell thick.
+// Cells at the edge of the bounds only count along the other axis.
+pub fn medial_axis<M>(blocked: &[(isize, isize)], bounds: BoundingBox) -> Result<Vec<bool>, VoronoiError>
+where
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let distances = distance_transform::<M>(blocked, bounds)?;
+
+    Ok(ridges(&distances, &bounds))
+}
+
+fn ridges(distances: &[f64], bounds: &BoundingBox) -> Vec<bool> {
+    let (width, height) = bounds.dimensions();
+    let distance_at = |x: usize, y: usize| Some(distances[x + y * width]).filter(|distance| distance.is_finite());
+    let ridge = |before: Option<f64>, here: f64, after: Option<f64>| match (before, after) {
+        (Some(before), Some(after)) => here > before && here >= after,
+        _ => false
+    };
+
+    let mut skeleton = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let here = match distance_at(x, y) {
+                Some(distance) if distance > 0.0 => distance,
+                _ => {
+                    skeleton.push(false);
+                    continue;
+                }
+            };
+
+            let left = x.checked_sub(1).and_then(|x| distance_at(x, y));
+            let right = (x + 1 < width).then(|| distance_at(x + 1, y)).flatten();
+            let up = y.checked_sub(1).and_then(|y| distance_at(x, y));
+            let down = (y + 1 < height).then(|| distance_at(x, y + 1)).flatten();
+
+            skeleton.push(ridge(left, here, right) || ridge(up, here, down));
+        }
+    }
+
+    skeleton
+}
+
+// Coordinates of the skeleton cells in row-major order
+pub fn skeleton_cells(skeleton: &[bool], bounds: &BoundingBox) -> Vec<GridIdx> {
+    bounds
+        .coordinates_iter()
+        .zip(skeleton)
+        .filter(|&(_, &on_axis)| on_axis)
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+// Route through the free cells that keeps as far from the blocked cells as
+// possible
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearancePath {
+    // Cells from the start to the goal, neighbours sharing an edge
+    pub cells: Vec<GridIdx>,
+    // Smallest distance to a blocked cell along the route between the cells
+    // where it joins and leaves the medial axis, the climbs from the
+    // endpoints can come closer
+    pub clearance: f64
+}
+
+// Finds the safest path between two free cells for a robot among the
+// obstacles. Both endpoints climb to higher distances from the blocked cells
+// until they reach the medial axis, or a cell with no higher neighbour. Between
+// those two cells the path keeps the largest clearance any route between them
+// can keep, and among the routes with that clearance the one with the lowest
+// sum of 1 / clearance over its cells is taken, which follows the medial axis
+// through open space. None if either end is blocked, outside of the bounds or
+// cut off from the other, and an error without any blocked cells to keep
+// clear of.
+pub fn max_clearance_path<M>(
+    blocked: &[(isize, isize)],
+    bounds: BoundingBox,
+    start: (isize, isize),
+    goal: (isize, isize)
+) -> Result<Option<ClearancePath>, VoronoiError>
+where
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let distances = distance_transform::<M>(blocked, bounds)?;
+    let skeleton = ridges(&distances, &bounds);
+    let free = |position: usize| Some(distances[position]).filter(|&distance| distance > 0.0);
+    let free_at = |idx: GridIdx| bounds.linear_idx(idx).and_then(free);
+
+    let (start, goal) = match (bounds.linear_idx(GridIdx::from(start)), bounds.linear_idx(GridIdx::from(goal))) {
+        (Some(start), Some(goal)) if free(start).is_some() && free(goal).is_some() => (start, goal),
+        _ => return Ok(None)
+    };
+
+    // Climbs to the neighbour furthest from the blocked cells until the
+    // medial axis or a local maximum is reached
+    let climb = |from: usize| {
+        let mut cells = vec![from];
+        let mut current = from;
+        while !skeleton[current] {
+            let higher = bounds
+                .idx_at(current)
+                .neighbors(&bounds)
+                .map(|neighbor| bounds.linear_idx(neighbor).expect("neighbors are inside of the bounds"))
+                .filter(|&neighbor| distances[neighbor] > distances[current])
+                .max_by(|&a, &b| distances[a].total_cmp(&distances[b]));
+            match higher {
+                Some(next) => {
+                    cells.push(next);
+                    current = next;
+                },
+                None => break
+            }
+        }
+        cells
+    };
+    let start_climb = climb(start);
+    let goal_climb = climb(goal);
+    let (entry, exit) = (start_climb[start_climb.len() - 1], goal_climb[goal_climb.len() - 1]);
+    let entry_idx = bounds.idx_at(entry);
+
+    // Largest clearance a route between the two ends on the medial axis can
+    // keep, spreading the smallest clearance seen so far with the widest
+    // routes settled first
+    let widest = propagate(&bounds, [(entry_idx, 0, -distances[entry])], |_, _, narrowest, neighbor| {
+        free_at(neighbor).map(|distance| narrowest.max(-distance))
+    });
+    let clearance = -widest.priority[exit];
+    if !clearance.is_finite() || widest.source[exit].is_none() {
+        return Ok(None);
+    }
+
+    // Cheapest route through the cells with at least that clearance
+    let cheapest = propagate(&bounds, [(entry_idx, 0, 1.0 / distances[entry])], |_, _, cost, neighbor| {
+        free_at(neighbor).filter(|&distance| distance >= clearance).map(|distance| cost + 1.0 / distance)
+    });
+    let mut route = Vec::new();
+    let mut current = Some(exit);
+    while let Some(position) = current {
+        route.push(position);
+        current = cheapest.parent[position];
+    }
+    route.reverse();
+
+    // Joins the climbs to the route, cutting out any loop where the path
+    // comes back to a cell it already went through
+    let joined = start_climb[..start_climb.len() - 1]
+        .iter()
+        .chain(&route)
+        .chain(goal_climb[..goal_climb.len() - 1].iter().rev());
+    let mut cells: Vec<GridIdx> = Vec::new();
+    for &position in joined {
+        let idx = bounds.idx_at(position);
+        if let Some(seen) = cells.iter().position(|&cell| cell == idx) {
+            cells.truncate(seen);
+        }
+        cells.push(idx);
+    }
+
+    Ok(Some(ClearancePath { cells, clearance }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Euclidean;
+    use crate::site::Point;
+
+    #[test]
+    fn corridor_center_line() {
+        // A corridor three cells wide between two walls, and one two cells wide
+        let bounds = BoundingBox::new(0, 0, 5, 4);
+        let mut walls: Vec<(isize, isize)> = (0..4).flat_map(|y| vec![(0, y), (4, y)]).collect();
+        let skeleton = medial_axis::<Euclidean>(&walls, bounds).unwrap();
+        let cells = skeleton_cells(&skeleton, &bounds);
+        assert_eq!(cells, (0..4).map(|y| GridIdx::from((2, y))).collect::<Vec<_>>());
+
+        walls.retain(|&(x, _)| x == 0);
+        walls.extend((0..4).map(|y| (3, y)));
+        let skeleton = medial_axis::<Euclidean>(&walls, BoundingBox::new(0, 0, 4, 4)).unwrap();
+        assert_eq!(skeleton.iter().filter(|&&on_axis| on_axis).count(), 4);
+    }
+
+    #[test]
+    fn keep_clear_of_walls() {
+        // A corridor between walls along the top and bottom rows
+        let bounds = BoundingBox::new(0, 0, 7, 5);
+        let walls: Vec<(isize, isize)> = (0..7).flat_map(|x| vec![(x, 0), (x, 4)]).collect();
+
+        let path = max_clearance_path::<Euclidean>(&walls, bounds, (0, 1), (6, 3)).unwrap().unwrap();
+        assert_eq!(path.clearance, 2.0);
+        assert_eq!(path.cells.first(), Some(&GridIdx::from((0, 1))));
+        assert_eq!(path.cells.last(), Some(&GridIdx::from((6, 3))));
+        assert!(path.cells[1..path.cells.len() - 1].iter().all(|idx| idx.coordinates().1 == 2));
+        for pair in path.cells.windows(2) {
+            let ((x, y), (next_x, next_y)) = (pair[0].coordinates(), pair[1].coordinates());
+            assert_eq!(x.abs_diff(next_x) + y.abs_diff(next_y), 1);
+        }
+
+        assert_eq!(max_clearance_path::<Euclidean>(&walls, bounds, (0, 0), (6, 3)), Ok(None));
+        let mut cut = walls.clone();
+        cut.extend((1..4).map(|y| (3, y)));
+        assert_eq!(max_clearance_path::<Euclidean>(&cut, bounds, (0, 2), (6, 2)), Ok(None));
+    }
+
+    #[test]
+    fn route_through_the_middle_of_a_corridor() {
+        // Both endpoints sit next to a wall of a corridor five cells wide
+        let bounds = BoundingBox::new(0, 0, 13, 7);
+        let walls: Vec<(isize, isize)> = (0..13).flat_map(|x| vec![(x, 0), (x, 6)]).collect();
+
+        let path = max_clearance_path::<Euclidean>(&walls, bounds, (1, 1), (11, 5)).unwrap().unwrap();
+        assert_eq!(path.clearance, 3.0);
+
+        let mut expected = vec![GridIdx::from((1, 1)), GridIdx::from((1, 2))];
+        expected.extend((1..12).map(|x| GridIdx::from((x, 3))));
+        expected.extend([GridIdx::from((11, 4)), GridIdx::from((11, 5))]);
+        assert_eq!(path.cells, expected);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::grid::BoundingBox;
+use crate::metric::Metric;
+use crate::site::{Site, Weight};
+
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"DVTS";
+pub const VERSION: u8 = 2;
+
+const FLAG_DISTANCES: u8 = 0b0000_0001;
+const NO_OWNER: u32 = u32::MAX;
+
+// Most sites reserved up front, counts in the header are not trusted
+const MAX_PREALLOCATED_SITES: usize = 1 << 16;
+
+// Computed tessellation as read back from the binary snapshot format
+//
+// Layout (all integers little endian):
+//   magic "DVTS", version: u8, flags: u8, label width in bytes: u8
+//   bounds: x_offset i64, y_offset i64, width u64, height u64
+//   site count u32, then per site: owner u32, x i64, y i64, weight f64
+//   labels: width * height owner ids, using the label width, all ones for none
+//   distances (if flagged): width * height f32
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    bounds: BoundingBox,
+    sites: Vec<(SiteOwner, (isize, isize, f64))>,
+    labels: Vec<Option<SiteOwner>>,
+    distances: Option<Vec<f32>>
+}
+
+impl Snapshot {
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    pub fn sites(&self) -> &[(SiteOwner, (isize, isize, f64))] {
+        &self.sites
+    }
+
+    // Owner of every cell in row-major order
+    pub fn labels(&self) -> &[Option<SiteOwner>] {
+        &self.labels
+    }
+
+    // Distance from every cell to its owner in row-major order, if it was saved
+    pub fn distances(&self) -> Option<&[f32]> {
+        self.distances.as_deref()
+    }
+}
+
+pub fn save<S, M, W>(tesselation: &VoronoiTesselation<S, M>, writer: &mut W, with_distances: bool) -> io::Result<()>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>,
+    W: Write
+{
+    let sites = tesselation.owned_sites();
+    let max_label = sites.iter().map(|&(owner, _)| owner.0).max().unwrap_or(0);
+    let label_width = label_width(max_label);
+
+    let flags = if with_distances { FLAG_DISTANCES } else { 0 };
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION, flags, label_width])?;
+
+    let bounds = tesselation.bounds();
+    let (x_offset, y_offset) = bounds.offset();
+    let (width, height) = bounds.dimensions();
+    writer.write_all(&(x_offset as i64).to_le_bytes())?;
+    writer.write_all(&(y_offset as i64).to_le_bytes())?;
+    writer.write_all(&(width as u64).to_le_bytes())?;
+    writer.write_all(&(height as u64).to_le_bytes())?;
+
+    writer.write_all(&(sites.len() as u32).to_le_bytes())?;
+    for &(owner, site) in &sites {
+        let (x, y) = site.coordinates();
+        writer.write_all(&owner.0.to_le_bytes())?;
+        writer.write_all(&(x as i64).to_le_bytes())?;
+        writer.write_all(&(y as i64).to_le_bytes())?;
+        writer.write_all(&site.weight().to_f64().to_le_bytes())?;
+    }
+
+    let mut labels = Vec::with_capacity(tesselation.cells().len() * label_width as usize);
+    for (_, cell) in tesselation.cells() {
+        let label = cell.owner().map_or(NO_OWNER, |owner| owner.0);
+        labels.extend_from_slice(&label.to_le_bytes()[..label_width as usize]);
+    }
+    writer.write_all(&labels)?;
+
+    if with_distances {
+        let mut distances = Vec::with_capacity(tesselation.cells().len() * 4);
+        for (idx, cell) in tesselation.cells() {
+            let distance = match cell.owner() {
+                Some(owner) => {
+                    let site = tesselation.site(owner).unwrap();
+                    M::distance(site, &idx).into() as f32
+                },
+                None => f32::NAN
+            };
+            distances.extend_from_slice(&distance.to_le_bytes());
+        }
+        writer.write_all(&distances)?;
+    }
+
+    Ok(())
+}
+
+pub fn load<R: Read>(reader: &mut R) -> io::Result<Snapshot> {
+    let magic: [u8; 4] = read_bytes(reader)?;
+    if &magic != MAGIC {
+        return Err(invalid_data("not a tesselation snapshot"));
+    }
+
+    let header: [u8; 3] = read_bytes(reader)?;
+    let (version, flags, label_width) = (header[0], header[1], header[2]);
+    if version != VERSION {
+        return Err(invalid_data("unsupported snapshot version"));
+    }
+    if label_width != 1 && label_width != 2 && label_width != 4 {
+        return Err(invalid_data("invalid label width"));
+    }
+
+    let x_offset = read_isize(reader)?;
+    let y_offset = read_isize(reader)?;
+    let width = read_usize(reader)?;
+    let height = read_usize(reader)?;
+    let bounds = BoundingBox::new(x_offset, y_offset, width, height);
+    bounds.validate().map_err(|error| invalid_data(&error.to_string()))?;
+
+    let num_sites = read_u32(reader)? as usize;
+    let mut sites = Vec::with_capacity(num_sites.min(MAX_PREALLOCATED_SITES));
+    for _ in 0..num_sites {
+        let owner = SiteOwner(read_u32(reader)?);
+        let x = read_isize(reader)?;
+        let y = read_isize(reader)?;
+        let weight = f64::from_bits(read_u64(reader)?);
+        sites.push((owner, (x, y, weight)));
+    }
+
+    let num_cells = width
+        .checked_mul(height)
+        .ok_or_else(|| invalid_data("bounds are too large"))?;
+    let none_label = NO_OWNER >> (32 - 8 * label_width as u32);
+    let labels_len = num_cells
+        .checked_mul(label_width as usize)
+        .ok_or_else(|| invalid_data("bounds are too large"))?;
+    let raw_labels = read_vec(reader, labels_len)?;
+    let labels = raw_labels
+        .chunks(label_width as usize)
+        .map(|chunk| {
+            let mut bytes = [0u8; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            match u32::from_le_bytes(bytes) {
+                label if label == none_label => Ok(None),
+                label if label as usize >= num_sites => Err(invalid_data("label past the number of sites")),
+                label => Ok(Some(SiteOwner(label)))
+            }
+        })
+        .collect::<io::Result<_>>()?;
+
+    let distances = if flags & FLAG_DISTANCES != 0 {
+        let distances_len = num_cells
+            .checked_mul(4)
+            .ok_or_else(|| invalid_data("bounds are too large"))?;
+        let raw_distances = read_vec(reader, distances_len)?;
+        let distances = raw_distances
+            .chunks(4)
+            .map(|chunk| f32::from_bits(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])))
+            .collect();
+
+        Some(distances)
+    } else {
+        None
+    };
+
+    Ok(Snapshot {
+        bounds,
+        sites,
+        labels,
+        distances
+    })
+}
+
+// Smallest number of bytes that can hold every label and the "no owner" marker
+fn label_width(max_label: u32) -> u8 {
+    if max_label < u8::MAX as u32 {
+        1
+    } else if max_label < u16::MAX as u32 {
+        2
+    } else {
+        4
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Running out of input midway is a malformed snapshot rather than an I/O
+// failure
+fn truncated(error: io::Error) -> io::Error {
+    if error.kind() == io::ErrorKind::UnexpectedEof {
+        invalid_data("snapshot is truncated")
+    } else {
+        error
+    }
+}
+
+fn read_bytes<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes).map_err(truncated)?;
+    Ok(bytes)
+}
+
+// Reads exactly len bytes, growing the buffer with the input that is really
+// there instead of trusting the length up front
+fn read_vec<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() == len { Ok(bytes) } else { Err(invalid_data("snapshot is truncated")) }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    read_bytes(reader).map(u32::from_le_bytes)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    read_bytes(reader).map(u64::from_le_bytes)
+}
+
+fn read_isize<R: Read>(reader: &mut R) -> io::Result<isize> {
+    let value = i64::from_le_bytes(read_bytes(reader)?);
+    isize::try_from(value).map_err(|_| invalid_data("coordinate does not fit in isize"))
+}
+
+fn read_usize<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(read_u64(reader)?).map_err(|_| invalid_data("dimension does not fit in usize"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+
+    #[test]
+    fn snapshot_round_trip() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (4, 4, 1f32), (0, 4, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        let mut buffer = Vec::new();
+        save(&tess, &mut buffer, true).unwrap();
+        let snapshot = load(&mut buffer.as_slice()).unwrap();
+
+        assert_eq!(snapshot.bounds(), tess.bounds());
+        assert_eq!(snapshot.sites().len(), 3);
+        let labels: Vec<Option<SiteOwner>> = tess.cells().iter().map(|(_, cell)| cell.owner()).collect();
+        assert_eq!(snapshot.labels(), labels.as_slice());
+        assert_eq!(snapshot.distances().unwrap().len(), labels.len());
+    }
+
+    #[test]
+    fn snapshot_rejects_bad_magic() {
+        let buffer = b"NOPE\x01\x00\x01";
+
+        assert!(load(&mut &buffer[..]).is_err());
+    }
+
+    #[test]
+    fn snapshot_rejects_truncated_input() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (4, 4, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        let mut buffer = Vec::new();
+        save(&tess, &mut buffer, true).unwrap();
+        for len in 0..buffer.len() {
+            let error = load(&mut &buffer[..len]).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "cut at {}", len);
+        }
+    }
+
+    #[test]
+    fn snapshot_rejects_unknown_labels() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (4, 4, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        // Without distances the labels are the last bytes, one per cell
+        let mut buffer = Vec::new();
+        save(&tess, &mut buffer, false).unwrap();
+        *buffer.last_mut().unwrap() = 2;
+
+        let error = load(&mut buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn snapshot_rejects_oversized_header() {
+        let header = |width: u64, height: u64, num_sites: u32| {
+            let mut buffer = MAGIC.to_vec();
+            buffer.extend_from_slice(&[VERSION, FLAG_DISTANCES, 4]);
+            buffer.extend_from_slice(&0i64.to_le_bytes());
+            buffer.extend_from_slice(&0i64.to_le_bytes());
+            buffer.extend_from_slice(&width.to_le_bytes());
+            buffer.extend_from_slice(&height.to_le_bytes());
+            buffer.extend_from_slice(&num_sites.to_le_bytes());
+            buffer
+        };
+        let load_kind = |buffer: Vec<u8>| load(&mut buffer.as_slice()).unwrap_err().kind();
+
+        // Cell count overflows, the bounds do not fit and the counts promise
+        // far more data than follows, none of which may be allocated up front
+        assert_eq!(load_kind(header(u64::MAX, 2, 0)), io::ErrorKind::InvalidData);
+        assert_eq!(load_kind(header(0, 5, 0)), io::ErrorKind::InvalidData);
+        assert_eq!(load_kind(header(1 << 31, 1 << 31, 0)), io::ErrorKind::InvalidData);
+        assert_eq!(load_kind(header(4, 4, u32::MAX)), io::ErrorKind::InvalidData);
+
+        let mut offsets = MAGIC.to_vec();
+        offsets.extend_from_slice(&[VERSION, 0, 1]);
+        offsets.extend_from_slice(&isize::MAX.to_le_bytes());
+        offsets.extend_from_slice(&0i64.to_le_bytes());
+        offsets.extend_from_slice(&2u64.to_le_bytes());
+        offsets.extend_from_slice(&2u64.to_le_bytes());
+        assert_eq!(load_kind(offsets), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::grid::GridIdx;
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+use std::f64::consts::PI;
+
+// Summary statistics of the number of cells in every region
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AreaStatistics {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    // 0 when all regions are the same size, approaching 1 as a single region
+    // takes all of the cells
+    pub gini: f64
+}
+
+// Counts of region areas falling in equally sized bins, starting from min
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaHistogram {
+    pub min: usize,
+    pub bin_width: usize,
+    pub counts: Vec<usize>
+}
+
+// Number of cells owned by every site, sorted by owner
+pub fn region_areas<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<(SiteOwner, usize)>
+where
+    S: Site,
+    M: Metric
+{
+    tesselation.summary().region_sizes
+}
+
+pub fn area_statistics<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Option<AreaStatistics>
+where
+    S: Site,
+    M: Metric
+{
+    let mut areas: Vec<usize> = region_areas(tesselation).into_iter().map(|(_, area)| area).collect();
+    if areas.is_empty() {
+        return None;
+    }
+    areas.sort_unstable();
+
+    let count = areas.len() as f64;
+    let total: usize = areas.iter().sum();
+    let mean = total as f64 / count;
+    let variance = areas.iter().map(|&area| (area as f64 - mean).powi(2)).sum::<f64>() / count;
+
+    let gini = if total == 0 {
+        0.0
+    } else {
+        let ranked: f64 = areas
+            .iter()
+            .enumerate()
+            .map(|(rank, &area)| (rank + 1) as f64 * area as f64)
+            .sum();
+        (2.0 * ranked) / (count * total as f64) - (count + 1.0) / count
+    };
+
+    Some(AreaStatistics {
+        min: areas[0],
+        max: areas[areas.len() - 1],
+        mean,
+        std_dev: variance.sqrt(),
+        gini
+    })
+}
+
+pub fn area_histogram<S, M>(tesselation: &VoronoiTesselation<S, M>, bins: usize) -> Option<AreaHistogram>
+where
+    S: Site,
+    M: Metric
+{
+    let areas: Vec<usize> = region_areas(tesselation).into_iter().map(|(_, area)| area).collect();
+    if areas.is_empty() || bins == 0 {
+        return None;
+    }
+
+    let min = *areas.iter().min().unwrap();
+    let max = *areas.iter().max().unwrap();
+    let bin_width = ((max - min) / bins + 1).max(1);
+
+    let mut counts = vec![0; bins];
+    for area in areas {
+        let bin = ((area - min) / bin_width).min(bins - 1);
+        counts[bin] += 1;
+    }
+
+    Some(AreaHistogram { min, bin_width, counts })
+}
+
+// Shape of a region from the second moments of its cells, each cell counting
+// as a unit square
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeStatistics {
+    pub owner: SiteOwner,
+    pub area: usize,
+    pub centroid: (f64, f64),
+    // Number of cell edges facing another region or the edge of the bounds
+    pub perimeter: usize,
+    // Full lengths of the axes of the ellipse with the same second moments
+    pub major_axis: f64,
+    pub minor_axis: f64,
+    // Angle of the major axis from the x axis in radians, in (-pi/2, pi/2],
+    // turning towards positive y
+    pub orientation: f64,
+    // 0 for a circle or square, approaching 1 as the region stretches out
+    pub eccentricity: f64,
+    // Polsby-Popper score 4 pi area / perimeter^2, about 0.785 for a square
+    pub compactness: f64
+}
+
+// Shape statistics of every region sorted by owner, all zero apart from the
+// centroid at the site for regions without cells
+pub fn shape_statistics<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<ShapeStatistics>
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let (x_offset, y_offset) = bounds.offset();
+    // Count, sums of x, y, x^2, y^2 and xy relative to the offset, perimeter
+    let mut sums = vec![(0usize, 0.0, 0.0, 0.0, 0.0, 0.0, 0usize); tesselation.len()];
+
+    for (idx, cell) in tesselation.cells() {
+        let owner = match cell.owner() {
+            Some(owner) => owner,
+            None => continue
+        };
+        let (x, y) = bounds.translate_idx(idx);
+        let (x, y) = (x as f64, y as f64);
+        let sum = &mut sums[owner.0 as usize];
+
+        sum.0 += 1;
+        sum.1 += x;
+        sum.2 += y;
+        sum.3 += x * x;
+        sum.4 += y * y;
+        sum.5 += x * y;
+        sum.6 += 4 - idx.neighbors(bounds).filter(|&neighbor| tesselation[neighbor].owner() == Some(owner)).count();
+    }
+
+    tesselation
+        .owners()
+        .zip(sums)
+        .map(|(owner, (count, x, y, xx, yy, xy, perimeter))| {
+            if count == 0 {
+                let site = tesselation.site(owner).expect("owners come from the tesselation");
+                return ShapeStatistics {
+                    owner,
+                    area: 0,
+                    centroid: site.position(),
+                    perimeter: 0,
+                    major_axis: 0.0,
+                    minor_axis: 0.0,
+                    orientation: 0.0,
+                    eccentricity: 0.0,
+                    compactness: 0.0
+                };
+            }
+
+            let n = count as f64;
+            let (mean_x, mean_y) = (x / n, y / n);
+            // A unit square adds 1/12 to the variance along both axes
+            let var_x = xx / n - mean_x * mean_x + 1.0 / 12.0;
+            let var_y = yy / n - mean_y * mean_y + 1.0 / 12.0;
+            let cov = xy / n - mean_x * mean_y;
+
+            let spread = ((var_x - var_y).powi(2) / 4.0 + cov * cov).sqrt();
+            let major = (var_x + var_y) / 2.0 + spread;
+            let minor = ((var_x + var_y) / 2.0 - spread).max(0.0);
+
+            ShapeStatistics {
+                owner,
+                area: count,
+                centroid: (x_offset as f64 + mean_x, y_offset as f64 + mean_y),
+                perimeter,
+                major_axis: 4.0 * major.sqrt(),
+                minor_axis: 4.0 * minor.sqrt(),
+                orientation: 0.5 * (2.0 * cov).atan2(var_x - var_y),
+                eccentricity: (1.0 - minor / major).sqrt(),
+                compactness: 4.0 * PI * n / (perimeter as f64).powi(2)
+            }
+        })
+        .collect()
+}
+
+// Convex hull of the cells of every region in grid coordinates, sorted by
+// owner. Hulls run counter-clockwise in a y-up frame from the cell with the
+// lowest x and then y, skipping cells along straight edges, and are empty for
+// regions without cells. Only the outermost cell of every row of a region is
+// kept while scanning, so the cells are never collected.
+pub fn convex_hulls<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<(SiteOwner, Vec<GridIdx>)>
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let (width, _) = bounds.dimensions();
+    let mut extremes: Vec<Vec<(isize, isize)>> = vec![Vec::new(); tesselation.len()];
+
+    for (y, row) in tesselation.cells().as_slice().chunks(width.max(1)).enumerate() {
+        // Leftmost and rightmost cell of every region in the row
+        let mut spans: Vec<Option<(GridIdx, GridIdx)>> = vec![None; tesselation.len()];
+        for (x, cell) in row.iter().enumerate() {
+            if let Some(owner) = cell.owner() {
+                let position = bounds.idx_at(x + y * width);
+                let span = &mut spans[owner.0 as usize];
+                *span = Some(span.map_or((position, position), |(first, _)| (first, position)));
+            }
+        }
+
+        for (points, span) in extremes.iter_mut().zip(spans) {
+            if let Some((first, last)) = span {
+                points.push(first.coordinates());
+                if last != first {
+                    points.push(last.coordinates());
+                }
+            }
+        }
+    }
+
+    tesselation
+        .owners()
+        .zip(extremes)
+        .map(|(owner, points)| (owner, monotone_chain(points).into_iter().map(GridIdx::from).collect()))
+        .collect()
+}
+
+// Andrew's monotone chain over the points, dropping collinear ones
+fn monotone_chain(mut points: Vec<(isize, isize)>) -> Vec<(isize, isize)> {
+    points.sort_unstable();
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: (isize, isize), a: (isize, isize), b: (isize, isize)| {
+        (a.0 - o.0) as i128 * (b.1 - o.1) as i128 - (a.1 - o.1) as i128 * (b.0 - o.0) as i128
+    };
+
+    let mut hull: Vec<(isize, isize)> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0 {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each half starts the other one
+        hull.pop();
+    }
+
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+
+    #[test]
+    fn symmetric_regions_are_uniform() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (5, 0, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 6, 1)).build().unwrap();
+        tess.compute();
+
+        let statistics = area_statistics(&tess).unwrap();
+        assert_eq!(statistics.min, 3);
+        assert_eq!(statistics.max, 3);
+        assert_eq!(statistics.std_dev, 0.0);
+        assert_eq!(statistics.gini, 0.0);
+
+        let histogram = area_histogram(&tess, 4).unwrap();
+        assert_eq!(histogram.counts.iter().sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn hulls_of_regions() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (5, 0, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 6, 3)).build().unwrap();
+        tess.compute();
+
+        let hulls = convex_hulls(&tess);
+        assert_eq!(hulls.len(), 2);
+        let corners = |points: &[(isize, isize)]| points.iter().map(|&point| GridIdx::from(point)).collect::<Vec<_>>();
+        assert_eq!(hulls[0], (SiteOwner(0), corners(&[(0, 0), (2, 0), (2, 2), (0, 2)])));
+        assert_eq!(hulls[1], (SiteOwner(1), corners(&[(3, 0), (5, 0), (5, 2), (3, 2)])));
+
+        assert_eq!(monotone_chain(vec![(0, 0), (1, 1), (2, 2)]), vec![(0, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn shapes_of_regions() {
+        let sites: Vec<(isize, isize, f32)> = vec![(1, 1, 1f32), (6, 1, 1f32)];
+
+        // A 4x4 square and an 8x4 strip
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 12, 4)).build().unwrap();
+        tess.compute();
+
+        let shapes = shape_statistics(&tess);
+        let square = shapes[0];
+        assert_eq!(square.area, 16);
+        assert_eq!(square.centroid, (1.5, 1.5));
+        assert_eq!(square.perimeter, 16);
+        assert!(square.eccentricity.abs() < 1e-9);
+        assert!((square.compactness - PI / 4.0).abs() < 1e-9);
+        assert!((square.major_axis - 4.0 * (16.0f64 / 12.0).sqrt()).abs() < 1e-9);
+
+        let strip = shapes[1];
+        assert_eq!(strip.area, 32);
+        assert_eq!(strip.orientation, 0.0);
+        assert!(strip.major_axis > strip.minor_axis);
+        assert!(strip.eccentricity > 0.8);
+        assert!(strip.compactness < square.compactness);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::cluster::weighted_centroids;
+use crate::discrete_voronoi::{DuplicatePolicy, VoronoiBuilder};
+use crate::error::VoronoiError;
+use crate::grid::BoundingBox;
+use crate::site::Point;
+
+#[cfg(feature = "image")]
+use image::GrayImage;
+
+// Places the points over the density raster, given in row-major order over
+// the bounds, and moves every point to the density weighted centroid of its
+// region for the given number of rounds. Points start out spread in
+// proportion to the density, so darker areas get more of them.
+pub fn stipple(
+    density: &[f64],
+    bounds: BoundingBox,
+    points: usize,
+    iterations: usize
+) -> Result<Vec<(f64, f64)>, VoronoiError> {
+    bounds.validate()?;
+    let (width, height) = bounds.dimensions();
+    if density.len() != width * height {
+        return Err(VoronoiError::BufferLength {
+            expected: width * height,
+            actual: density.len()
+        });
+    }
+
+    let mut sites = initial_points(density, &bounds, points);
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+
+    for _ in 0..iterations {
+        sites = relax(&sites, density, bounds)?;
+    }
+
+    Ok(sites)
+}
+
+// Darkness of every pixel in row-major order, 1 for black and 0 for white
+#[cfg(feature = "image")]
+pub fn density_from_image(image: &GrayImage) -> Vec<f64> {
+    image.pixels().map(|pixel| 1.0 - f64::from(pixel.0[0]) / 255.0).collect()
+}
+
+// Walks the cells in row-major order and places a point every time the
+// running total of the density passes the next of evenly spaced marks
+fn initial_points(density: &[f64], bounds: &BoundingBox, points: usize) -> Vec<(f64, f64)> {
+    let total: f64 = density.iter().map(|value| value.max(0.0)).sum();
+    if points == 0 || total <= 0.0 {
+        return Vec::new();
+    }
+
+    let step = total / points as f64;
+    let mut next_mark = step / 2.0;
+    let mut running = 0.0;
+    let mut sites = Vec::with_capacity(points);
+
+    for (idx, &value) in bounds.coordinates_iter().zip(density) {
+        running += value.max(0.0);
+        while running > next_mark && sites.len() < points {
+            let (x, y) = idx.coordinates();
+            sites.push((x as f64, y as f64));
+            next_mark += step;
+        }
+    }
+
+    sites
+}
+
+// One round of weighted Lloyd relaxation, points whose region has no density
+// stay where they are
+fn relax(sites: &[(f64, f64)], density: &[f64], bounds: BoundingBox) -> Result<Vec<(f64, f64)>, VoronoiError> {
+    let mut tesselation = VoronoiBuilder::new(sites.iter().copied())
+        .bounds(bounds)
+        .duplicates(DuplicatePolicy::AllowDuplicates)
+        .build()?;
+    tesselation.compute();
+
+    let mut moved = sites.to_vec();
+    let centroids = weighted_centroids(&tesselation, density)?;
+    for (owner, centroid) in tesselation.owners().zip(centroids) {
+        if let (Some(index), Some(centroid)) = (tesselation.original_index(owner), centroid) {
+            moved[index] = centroid;
+        }
+    }
+
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_follow_density() {
+        // Only the right half of the raster is dark
+        let bounds = BoundingBox::new(0, 0, 10, 6);
+        let density: Vec<f64> = bounds
+            .coordinates_iter()
+            .map(|idx| if idx.coordinates().0 >= 5 { 1.0 } else { 0.0 })
+            .collect();
+
+        let points = stipple(&density, bounds, 6, 4).unwrap();
+        assert_eq!(points.len(), 6);
+        assert!(points.iter().all(|&(x, y)| x >= 5.0 && (0.0..6.0).contains(&y)));
+
+        assert_eq!(stipple(&density[1..], bounds, 6, 4), Err(VoronoiError::BufferLength { expected: 60, actual: 59 }));
+        assert_eq!(stipple(&vec![0.0; 60], bounds, 6, 4), Err(VoronoiError::NoSites));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::discrete_voronoi::{check_site_count, resolve_claims, Resolution, SiteOwner, TieBreak};
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::site::Site;
+
+// Computes the tesselation of the bounds in horizontal bands of rows, handing
+// every finished band to the sink and keeping only one band in memory. Each
+// band is grown with the given number of extra rows above and below it. Sites
+// inside the grown band are seeded from their own cell. The rows on the edges
+// of the grown band facing the rest of the bounds are seeded with the site
+// closest to each of their cells, found by measuring the distance to every
+// site, so regions of sites above or below reach into the band from where
+// they really cross it. Cells are labelled with the index of their site in
+// the input, so labels agree between bands, and equal distances go to the
+// earlier site. The overlap gives regions reaching into the band from outside
+// room to grow around their neighbours.
+pub fn compute_bands<S, M, F>(
+    sites: &[S],
+    bounds: BoundingBox,
+    band_height: usize,
+    overlap: usize,
+    mut sink: F
+) -> Result<(), VoronoiError>
+where
+    S: Site,
+    M: Metric,
+    F: FnMut(&BoundingBox, &[Option<usize>])
+{
+    let (width, height) = bounds.dimensions();
+    if width == 0 || height == 0 || band_height == 0 {
+        return Err(VoronoiError::ZeroAreaBounds);
+    }
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+    check_site_count(sites.len())?;
+
+    // Sites outside of the bounds do not own any cell of a single computation
+    // either
+    let inside: Vec<usize> = (0..sites.len())
+        .filter(|&index| GridIdx::from(sites[index].coordinates()).inside(&bounds))
+        .collect();
+    if inside.is_empty() {
+        return Err(VoronoiError::NoSitesInBounds);
+    }
+
+    let (x_offset, y_offset) = bounds.offset();
+    let mut labels = Vec::with_capacity(width * band_height);
+
+    for band_start in (0..height).step_by(band_height) {
+        let rows = band_height.min(height - band_start);
+        let band = BoundingBox::new(x_offset, y_offset + band_start as isize, width, rows);
+
+        let grown_start = band_start.saturating_sub(overlap);
+        let grown_end = (band_start + rows + overlap).min(height);
+        let grown = BoundingBox::new(x_offset, y_offset + grown_start as isize, width, grown_end - grown_start);
+
+        let mut edges = Vec::new();
+        if grown_start > 0 {
+            edges.push(y_offset + grown_start as isize);
+        }
+        if grown_end < height {
+            edges.push(y_offset + grown_end as isize - 1);
+        }
+        let edge_seeds: Vec<(GridIdx, SiteOwner)> = edges
+            .into_iter()
+            .flat_map(|y| (0..width as isize).map(move |x| GridIdx::from((x_offset + x, y))))
+            .map(|idx| (idx, closest_site::<S, M>(sites, &inside, idx)))
+            .collect();
+        let own_seeds = inside
+            .iter()
+            .map(|&index| (GridIdx::from(sites[index].coordinates()), SiteOwner(index as u32)));
+
+        let grown_labels = grow_band::<S, M>(sites, &grown, own_seeds.chain(edge_seeds).collect());
+
+        let skipped = (band_start - grown_start) * width;
+        labels.clear();
+        let band_labels = &grown_labels[skipped..skipped + width * rows];
+        labels.extend(band_labels.iter().map(|label| label.map(|owner| owner.0 as usize)));
+
+        sink(&band, &labels);
+    }
+
+    Ok(())
+}
+
+// Site closest to the cell among the given ones, equal distances go to the
+// earlier site
+fn closest_site<S: Site, M: Metric>(sites: &[S], candidates: &[usize], idx: GridIdx) -> SiteOwner {
+    let candidates = candidates.iter().map(|&index| (SiteOwner(index as u32), index, &sites[index]));
+    match resolve_claims::<S, M, _>(idx, None, candidates, TieBreak::FirstSite) {
+        Resolution::Claimed(owner) => owner,
+        _ => unreachable!("A cell without an owner always goes to one of the sites")
+    }
+}
+
+// Grows regions over the bounds from the seed cells like
+// VoronoiTesselation::compute(), owners are the positions of the sites in the
+// input. Each step offers the neighbours of the cells claimed in the last step
+// to their owners, and a claim takes a cell from its owner when the claimant
+// is strictly closer or as close and earlier in the input.
+fn grow_band<S: Site, M: Metric>(
+    sites: &[S],
+    bounds: &BoundingBox,
+    mut claims: Vec<(GridIdx, SiteOwner)>
+) -> Vec<Option<SiteOwner>> {
+    let (width, height) = bounds.dimensions();
+    let mut labels = vec![None; width * height];
+
+    claims.retain(|(idx, _)| idx.inside(bounds));
+    while !claims.is_empty() {
+        claims.sort_unstable();
+        claims.dedup();
+
+        let mut frontier = Vec::new();
+        for cell_claims in claims.chunk_by(|a, b| a.0 == b.0) {
+            let idx = cell_claims[0].0;
+            let position = bounds.linear_idx(idx).expect("Claims are inside of the bounds");
+            let owner = labels[position];
+            // The current owner goes first so it keeps the cell on ties it wins
+            let candidates = owner
+                .iter()
+                .chain(cell_claims.iter().map(|(_, claimant)| claimant))
+                .map(|&candidate| (candidate, candidate.0 as usize, &sites[candidate.0 as usize]));
+
+            let resolution = resolve_claims::<S, M, _>(idx, owner, candidates, TieBreak::FirstSite);
+            if let Resolution::Claimed(winner) = resolution {
+                labels[position] = Some(winner);
+                frontier.push((idx, winner));
+            }
+        }
+
+        claims.clear();
+        for (idx, owner) in frontier {
+            for neighbor in idx.neighbors(bounds) {
+                let position = bounds.linear_idx(neighbor).expect("Neighbours are inside of the bounds");
+                if labels[position] != Some(owner) {
+                    claims.push((neighbor, owner));
+                }
+            }
+        }
+    }
+
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::metric::{Euclidean, MultWeightedEuclidean};
+
+    #[test]
+    fn compute_in_bands() {
+        let sites: Vec<(isize, isize, f32)> = vec![(1, 1, 1f32), (8, 2, 1f32), (3, 9, 1f32), (9, 12, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 11, 14);
+
+        let mut full = VoronoiBuilder::new(sites.clone()).bounds(bounds).build().unwrap();
+        full.compute();
+        let expected: Vec<Option<usize>> = full
+            .cells()
+            .iter()
+            .map(|(_, cell)| cell.owner().and_then(|owner| full.original_index(owner)))
+            .collect();
+
+        let mut labels = Vec::new();
+        let mut bands = 0;
+        compute_bands::<_, Euclidean, _>(&sites, bounds, 4, 6, |band, band_labels| {
+            assert_eq!(band.dimensions().0, 11);
+            labels.extend_from_slice(band_labels);
+            bands += 1;
+        })
+        .unwrap();
+
+        assert_eq!(bands, 4);
+        assert_eq!(labels, expected);
+    }
+
+    #[test]
+    fn label_bands_without_sites() {
+        let sites: Vec<(isize, isize, f32)> = vec![(2, 1, 1f32), (7, 2, 1f32), (4, 0, 1f32), (20, 3, 1f32)];
+        let bounds = BoundingBox::new(0, 0, 10, 16);
+
+        let mut full = VoronoiBuilder::new(sites.clone()).bounds(bounds).build().unwrap();
+        full.compute();
+        let expected: Vec<Option<usize>> = full
+            .cells()
+            .iter()
+            .map(|(_, cell)| cell.owner().and_then(|owner| full.original_index(owner)))
+            .collect();
+        assert!(expected.iter().all(Option::is_some));
+
+        // Only the first band has sites in reach, the others lie well beyond
+        // the overlap
+        let mut labels = Vec::new();
+        compute_bands::<_, Euclidean, _>(&sites, bounds, 4, 1, |_, band_labels| {
+            labels.extend_from_slice(band_labels);
+        })
+        .unwrap();
+        assert_eq!(labels, expected);
+
+        let outside = [(20isize, 3isize, 1f32)];
+        let result = compute_bands::<_, Euclidean, _>(&outside, bounds, 4, 1, |_, _| {});
+        assert_eq!(result, Err(VoronoiError::NoSitesInBounds));
+    }
+
+    #[test]
+    fn seed_bands_from_sites_above() {
+        // Both sites lie in the first band and are pulled onto the same cell
+        // of the later bands, which is closer to B. B owns a disk around it
+        // though, and everything else belongs to the heavier A.
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 2f32), (0, 9, 1f32)];
+        let bounds = BoundingBox::new(-60, 0, 70, 24);
+
+        let mut full = VoronoiBuilder::new(sites.clone())
+            .metric::<MultWeightedEuclidean>()
+            .bounds(bounds)
+            .build()
+            .unwrap();
+        full.compute();
+        let expected: Vec<Option<usize>> = full
+            .cells()
+            .iter()
+            .map(|(_, cell)| cell.owner().and_then(|owner| full.original_index(owner)))
+            .collect();
+
+        let mut labels = Vec::new();
+        let mut owned_by_a = 0;
+        compute_bands::<_, MultWeightedEuclidean, _>(&sites, bounds, 4, 1, |band, band_labels| {
+            let (_, y_offset) = band.offset();
+            if y_offset >= 12 {
+                owned_by_a += band_labels.iter().filter(|&&label| label == Some(0)).count();
+            }
+            labels.extend_from_slice(band_labels);
+        })
+        .unwrap();
+
+        assert!(owned_by_a > 0);
+        assert_eq!(labels, expected);
+    }
+}
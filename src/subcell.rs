@@ -0,0 +1,138 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+// Share of every owner in a cell, sorted by owner, summing to 1 for cells
+// with any owner around them
+pub type CellShares = Vec<(SiteOwner, f64)>;
+
+// Estimates how much of every cell each site owns by measuring an n x n grid
+// of points spread evenly over the cell against the exact site positions,
+// returned in row-major order. Cells surrounded by their own region belong to
+// it in full without sampling, cells on a border are shared among the owners
+// of the 3x3 block around them. Renders can blend the colors of the owners
+// by their shares to smooth the borders.
+pub fn fractional_ownership<S, M>(tesselation: &VoronoiTesselation<S, M>, samples: usize) -> Vec<CellShares>
+where
+    S: Site,
+    M: Metric
+{
+    let samples = samples.max(1);
+
+    tesselation
+        .cells()
+        .iter()
+        .map(|(idx, _)| {
+            let counts = sample_cell(tesselation, idx, samples);
+            let total: usize = counts.iter().map(|&(_, count)| count).sum();
+            counts.into_iter().map(|(owner, count)| (owner, count as f64 / total as f64)).collect()
+        })
+        .collect()
+}
+
+// Area owned by every site counting the shares of the border cells, sorted
+// by owner
+pub fn fractional_areas<S, M>(tesselation: &VoronoiTesselation<S, M>, samples: usize) -> Vec<(SiteOwner, f64)>
+where
+    S: Site,
+    M: Metric
+{
+    let mut areas: Vec<(SiteOwner, f64)> = tesselation.owners().map(|owner| (owner, 0.0)).collect();
+    for shares in fractional_ownership(tesselation, samples) {
+        for (owner, share) in shares {
+            areas[owner.0 as usize].1 += share;
+        }
+    }
+
+    areas
+}
+
+// Number of the n x n points of the cell closest to every owner of the 3x3
+// block around it, sorted by owner. Ties go to the lower id and cells
+// surrounded by a single owner count all points for it.
+pub(crate) fn sample_cell<S, M>(
+    tesselation: &VoronoiTesselation<S, M>,
+    idx: GridIdx,
+    samples: usize
+) -> Vec<(SiteOwner, usize)>
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let mut owners: Vec<Option<SiteOwner>> = block(bounds, idx).map(|idx| tesselation[idx].owner()).collect();
+    owners.sort_unstable();
+    owners.dedup();
+
+    let candidates: Vec<SiteOwner> = owners.iter().flatten().copied().collect();
+    match (owners.as_slice(), candidates.as_slice()) {
+        (_, []) => return Vec::new(),
+        ([Some(owner)], _) => return vec![(*owner, samples * samples)],
+        _ => {}
+    }
+
+    let (x, y) = idx.coordinates();
+    let mut counts: Vec<(SiteOwner, usize)> = candidates.iter().map(|&owner| (owner, 0)).collect();
+    for row in 0..samples {
+        for column in 0..samples {
+            let point = (
+                x as f64 - 0.5 + (column as f64 + 0.5) / samples as f64,
+                y as f64 - 0.5 + (row as f64 + 0.5) / samples as f64
+            );
+
+            let mut closest: Option<(usize, M::Output)> = None;
+            for (position, &owner) in candidates.iter().enumerate() {
+                let site = tesselation.site(owner).expect("owners come from the tesselation");
+                let distance = M::distance(site, &point);
+                if closest.as_ref().is_none_or(|(_, best)| distance < *best) {
+                    closest = Some((position, distance));
+                }
+            }
+
+            if let Some((position, _)) = closest {
+                counts[position].1 += 1;
+            }
+        }
+    }
+
+    counts.retain(|&(_, count)| count > 0);
+    counts
+}
+
+// The cell and its neighbours, including diagonal ones, inside of the bounds
+fn block(bounds: &BoundingBox, idx: GridIdx) -> impl Iterator<Item = GridIdx> + '_ {
+    let (x, y) = idx.coordinates();
+
+    (-1..=1)
+        .flat_map(move |dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter_map(move |(dx, dy)| Some(GridIdx::from((x.checked_add(dx)?, y.checked_add(dy)?))))
+        .filter(move |neighbor| neighbor.inside(bounds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::metric::Euclidean;
+
+    #[test]
+    fn share_border_cells() {
+        // The border runs through the middle of the cells in column 2
+        let sites = vec![(0.0, 0.0), (4.0, 0.0)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Euclidean>()
+            .bounds(BoundingBox::new(0, 0, 5, 1))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let shares = fractional_ownership(&tess, 4);
+        assert_eq!(shares[0], vec![(SiteOwner(0), 1.0)]);
+        assert_eq!(shares[2], vec![(SiteOwner(0), 0.5), (SiteOwner(1), 0.5)]);
+        assert_eq!(shares[4], vec![(SiteOwner(1), 1.0)]);
+
+        let areas = fractional_areas(&tess, 4);
+        assert_eq!(areas, vec![(SiteOwner(0), 2.5), (SiteOwner(1), 2.5)]);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::discrete_voronoi::SiteOwner;
+use crate::grid::BoundingBox;
+
+use std::fmt;
+
+// Basic numbers describing the state of a tesselation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub bounds: BoundingBox,
+    pub sites: usize,
+    // Number of cells owned by every site, sorted by owner
+    pub region_sizes: Vec<(SiteOwner, usize)>,
+    pub unclaimed: usize,
+    pub contested: usize,
+    pub steps: u32,
+    pub complete: bool
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (x_offset, y_offset) = self.bounds.offset();
+        let (width, height) = self.bounds.dimensions();
+        let status = if self.complete { "complete" } else { "incomplete" };
+
+        writeln!(f, "bounds: {}x{} at ({}, {})", width, height, x_offset, y_offset)?;
+        writeln!(f, "sites: {}", self.sites)?;
+        writeln!(f, "status: {} after {} steps", status, self.steps)?;
+        writeln!(f, "unclaimed cells: {}", self.unclaimed)?;
+        writeln!(f, "contested cells: {}", self.contested)?;
+        write!(f, "regions:")?;
+        for &(owner, size) in &self.region_sizes {
+            write!(f, "\n  {}: {} cells", owner.0, size)?;
+        }
+
+        Ok(())
+    }
+}
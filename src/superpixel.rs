@@ -0,0 +1,182 @@
+use image::RgbImage;
+
+// Settings for superpixel segmentation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlicParams {
+    // Rough number of superpixels, seeded on a regular grid
+    pub segments: usize,
+    // Weight of the spatial distance against the color distance, higher values
+    // give more compact and regular superpixels
+    pub compactness: f64,
+    // Rounds of assignment and center updates
+    pub iterations: usize
+}
+
+impl Default for SlicParams {
+    fn default() -> Self {
+        SlicParams {
+            segments: 100,
+            compactness: 10.0,
+            iterations: 10
+        }
+    }
+}
+
+// Center of a superpixel, the mean position and color of its pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuperpixelCenter {
+    pub x: f64,
+    pub y: f64,
+    pub color: [f64; 3]
+}
+
+// Superpixel label of every pixel in row-major order, indexing the centers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Superpixels {
+    pub width: u32,
+    pub height: u32,
+    pub labels: Vec<u32>,
+    pub centers: Vec<SuperpixelCenter>
+}
+
+impl Superpixels {
+    pub fn label_at(&self, x: u32, y: u32) -> Option<u32> {
+        if x < self.width && y < self.height {
+            Some(self.labels[(x + y * self.width) as usize])
+        } else {
+            None
+        }
+    }
+}
+
+// SLIC segmentation: sites seeded on a grid claim the pixels within twice the
+// grid spacing under a distance mixing color difference with spatial
+// distance, then move to the mean of the pixels they claimed. Colors are
+// compared in RGB, and superpixels are not forced to be connected.
+// This does not go through cluster::refine() and the metrics: a Metric only
+// sees the position and weight of a site and a cell, so it cannot read the
+// color of the pixel under the cell, and refine() assigns cells by growing
+// connected regions over the whole grid where SLIC searches a window around
+// every center.
+pub fn slic(image: &RgbImage, params: &SlicParams) -> Superpixels {
+    let (width, height) = image.dimensions();
+    let pixels = (width as usize) * (height as usize);
+    if pixels == 0 {
+        return Superpixels {
+            width,
+            height,
+            labels: Vec::new(),
+            centers: Vec::new()
+        };
+    }
+
+    let spacing = (pixels as f64 / params.segments.max(1) as f64).sqrt().max(1.0);
+    let color_at = |x: u32, y: u32| image.get_pixel(x, y).0.map(f64::from);
+
+    let mut centers = Vec::new();
+    let mut y = spacing / 2.0;
+    while y < height as f64 {
+        let mut x = spacing / 2.0;
+        while x < width as f64 {
+            centers.push(SuperpixelCenter {
+                x,
+                y,
+                color: color_at(x as u32, y as u32)
+            });
+            x += spacing;
+        }
+        y += spacing;
+    }
+
+    let spatial_scale = (params.compactness / spacing).powi(2);
+    let mut labels = vec![0u32; pixels];
+    let mut distances = vec![f64::INFINITY; pixels];
+
+    for _ in 0..params.iterations.max(1) {
+        distances.iter_mut().for_each(|distance| *distance = f64::INFINITY);
+
+        for (label, center) in centers.iter().enumerate() {
+            let x_range = window(center.x, spacing, width);
+            let y_range = window(center.y, spacing, height);
+
+            for y in y_range {
+                for x in x_range.clone() {
+                    let color = color_at(x, y);
+                    let color_distance: f64 = (0..3).map(|c| (color[c] - center.color[c]).powi(2)).sum();
+                    let spatial_distance = (x as f64 - center.x).powi(2) + (y as f64 - center.y).powi(2);
+                    let distance = color_distance + spatial_scale * spatial_distance;
+
+                    let idx = (x + y * width) as usize;
+                    if distance < distances[idx] {
+                        distances[idx] = distance;
+                        labels[idx] = label as u32;
+                    }
+                }
+            }
+        }
+
+        // Centers without pixels stay where they are
+        let mut sums = vec![(0.0, 0.0, [0.0; 3], 0usize); centers.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let sum = &mut sums[labels[(x + y * width) as usize] as usize];
+                let color = color_at(x, y);
+                sum.0 += x as f64;
+                sum.1 += y as f64;
+                (0..3).for_each(|c| sum.2[c] += color[c]);
+                sum.3 += 1;
+            }
+        }
+        for (center, &(x, y, color, count)) in centers.iter_mut().zip(&sums) {
+            if count > 0 {
+                let count = count as f64;
+                *center = SuperpixelCenter {
+                    x: x / count,
+                    y: y / count,
+                    color: color.map(|c| c / count)
+                };
+            }
+        }
+    }
+
+    Superpixels {
+        width,
+        height,
+        labels,
+        centers
+    }
+}
+
+// Pixels within twice the spacing of the center along one axis
+fn window(center: f64, spacing: f64, length: u32) -> std::ops::Range<u32> {
+    let start = (center - 2.0 * spacing).floor().max(0.0) as u32;
+    let end = ((center + 2.0 * spacing).ceil() as u32 + 1).min(length);
+
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn split_by_color() {
+        let image = RgbImage::from_fn(8, 4, |x, _| if x < 3 { Rgb([200, 0, 0]) } else { Rgb([0, 0, 200]) });
+        let params = SlicParams {
+            segments: 2,
+            compactness: 1.0,
+            iterations: 5
+        };
+
+        let superpixels = slic(&image, &params);
+        assert_eq!(superpixels.centers.len(), 2);
+        for y in 0..4 {
+            for x in 0..8 {
+                assert_eq!(superpixels.label_at(x, y), Some(if x < 3 { 0 } else { 1 }));
+            }
+        }
+        assert_eq!(superpixels.centers[0].color, [200.0, 0.0, 0.0]);
+        assert_eq!(superpixels.label_at(8, 0), None);
+    }
+}
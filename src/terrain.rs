@@ -0,0 +1,142 @@
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::propagate::propagate;
+use crate::site::Point;
+
+// Cost of growing a region from a cell into its neighbour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainParams {
+    // Cost of a step between cells at the same elevation
+    pub step_cost: f64,
+    // Extra cost per unit of elevation change in a step
+    pub slope_penalty: f64,
+    // Only charge the penalty for climbing, so regions flow freely downhill
+    pub uphill_only: bool
+}
+
+impl Default for TerrainParams {
+    fn default() -> Self {
+        TerrainParams {
+            step_cost: 1.0,
+            slope_penalty: 1.0,
+            uphill_only: false
+        }
+    }
+}
+
+// Region of every cell after growing over the terrain, along with the cost of
+// the cheapest path from its site, in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainRegions {
+    bounds: BoundingBox,
+    costs: Vec<f64>,
+    owners: Vec<Option<usize>>
+}
+
+impl TerrainRegions {
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    // Infinite for cells no region reached
+    pub fn costs(&self) -> &[f64] {
+        &self.costs
+    }
+
+    // Positions of the sites in the input
+    pub fn owners(&self) -> &[Option<usize>] {
+        &self.owners
+    }
+
+    pub fn owner_at(&self, coordinates: (isize, isize)) -> Option<usize> {
+        let position = self.bounds.linear_idx(GridIdx::from(coordinates))?;
+        self.owners[position]
+    }
+
+    // None outside of the bounds and for cells no region reached
+    pub fn cost_at(&self, coordinates: (isize, isize)) -> Option<f64> {
+        let position = self.bounds.linear_idx(GridIdx::from(coordinates))?;
+        Some(self.costs[position]).filter(|cost| cost.is_finite())
+    }
+}
+
+// Grows a region from every site over the elevation raster, given in
+// row-major order over the bounds, where every cell goes to the site with the
+// cheapest path to it. Steps across steep slopes are expensive, so borders
+// settle along ridgelines and valleys instead of halfway between the sites.
+// Negative step costs count as free, ties go to the earlier site and sites
+// outside of the bounds are ignored.
+pub fn terrain_regions<P>(
+    sites: &[P],
+    elevation: &[f64],
+    bounds: BoundingBox,
+    params: &TerrainParams
+) -> Result<TerrainRegions, VoronoiError>
+where
+    P: Point
+{
+    bounds.validate()?;
+    let (width, height) = bounds.dimensions();
+    if elevation.len() != width * height {
+        return Err(VoronoiError::BufferLength {
+            expected: width * height,
+            actual: elevation.len()
+        });
+    }
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+
+    let height_at = |idx: GridIdx| elevation[bounds.linear_idx(idx).expect("grown cells are inside of the bounds")];
+    let step = |from: GridIdx, to: GridIdx| {
+        let rise = height_at(to) - height_at(from);
+        let rise = if params.uphill_only { rise.max(0.0) } else { rise.abs() };
+        (params.step_cost + params.slope_penalty * rise).max(0.0)
+    };
+
+    let seeds = sites.iter().enumerate().map(|(site, point)| (GridIdx::from(point.coordinates()), site, 0.0));
+    let propagation = propagate(&bounds, seeds, |_, from, cost, to| Some(cost + step(from, to)));
+
+    Ok(TerrainRegions {
+        bounds,
+        costs: propagation.priority,
+        owners: propagation.source
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borders_follow_ridge() {
+        // A ridge along x = 2 is closer to the left site than the midpoint
+        let bounds = BoundingBox::new(0, 0, 9, 3);
+        let elevation: Vec<f64> = bounds
+            .coordinates_iter()
+            .map(|idx| if idx.coordinates().0 == 2 { 10.0 } else { 0.0 })
+            .collect();
+        let sites = [(0isize, 1isize), (8, 1)];
+
+        let regions = terrain_regions(&sites, &elevation, bounds, &TerrainParams::default()).unwrap();
+        for y in 0..3 {
+            assert_eq!(regions.owner_at((1, y)), Some(0));
+            assert_eq!(regions.owner_at((3, y)), Some(1));
+        }
+        assert_eq!(regions.cost_at((3, 1)), Some(5.0));
+
+        // Without the penalty the border is back in the middle
+        let flat = TerrainParams {
+            slope_penalty: 0.0,
+            ..TerrainParams::default()
+        };
+        let regions = terrain_regions(&sites, &elevation, bounds, &flat).unwrap();
+        assert_eq!(regions.owner_at((3, 1)), Some(0));
+        assert_eq!(regions.owner_at((5, 1)), Some(1));
+
+        assert_eq!(
+            terrain_regions(&sites, &elevation[1..], bounds, &flat),
+            Err(VoronoiError::BufferLength { expected: 27, actual: 26 })
+        );
+    }
+}
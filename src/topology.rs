@@ -0,0 +1,504 @@
+use crate::discrete_voronoi::{SiteOwner, VoronoiTesselation};
+use crate::grid::GridIdx;
+use crate::metric::Metric;
+use crate::site::{Point, Site};
+
+use std::collections::{BTreeSet, HashMap};
+
+// A place where three or more regions meet. The position is the top left
+// cell of the 2x2 block of cells that touches all of the owners.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoronoiVertex {
+    pub position: GridIdx,
+    // Distinct owners of the block, sorted by id
+    pub owners: Vec<SiteOwner>
+}
+
+// Scans every 2x2 block of cells and reports the ones with at least three
+// distinct owners, in row-major order. Unowned cells do not count as a region.
+pub fn vertices<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<VoronoiVertex>
+where
+    S: Site,
+    M: Metric
+{
+    let (width, height) = tesselation.bounds().dimensions();
+    let mut vertices = Vec::new();
+
+    if width < 2 || height < 2 {
+        return vertices;
+    }
+
+    let cells = tesselation.cells();
+    for y in 0..height - 1 {
+        for x in 0..width - 1 {
+            let top_left = y * width + x;
+            let block = [top_left, top_left + 1, top_left + width, top_left + width + 1];
+
+            let mut owners: Vec<SiteOwner> = block.iter().filter_map(|&idx| cells[idx].owner()).collect();
+            owners.sort_unstable_by_key(|owner| owner.0);
+            owners.dedup();
+
+            if owners.len() >= 3 {
+                vertices.push(VoronoiVertex {
+                    position: tesselation.bounds().idx_at(top_left),
+                    owners
+                });
+            }
+        }
+    }
+
+    vertices
+}
+
+impl Point for VoronoiVertex {
+    fn coordinates(&self) -> (isize, isize) {
+        self.position.coordinates()
+    }
+}
+
+// Junction of the corridor graph, a cluster of touching vertices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorridorNode {
+    // First vertex of the cluster in row-major order
+    pub position: GridIdx,
+    // Regions meeting at the junction, sorted by id
+    pub owners: Vec<SiteOwner>
+}
+
+// Passage along the border between two regions, joining two junctions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corridor {
+    pub from: usize,
+    pub to: usize,
+    // The two sites the corridor runs between
+    pub between: (SiteOwner, SiteOwner),
+    // Number of border cells on one side of the corridor
+    pub length: f64,
+    // Smallest distance from a border cell to its site, the narrowest point
+    pub clearance: f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorridorGraph {
+    pub nodes: Vec<CorridorNode>,
+    pub edges: Vec<Corridor>
+}
+
+// Navigation graph over a tesselation of obstacle sites. Borders between
+// regions are the paths furthest from the obstacles on either side, they
+// meet at the vertices, which are merged into junctions where they touch.
+// Every pair of regions sharing a border gives a corridor for each stretch of
+// that border running from one junction to another, with the length and
+// clearance of that stretch alone. A stretch reaching more than two junctions
+// joins every pair of them, stretches that only run into the edge of the
+// bounds reach a single junction and give no corridor.
+pub fn corridor_graph<S, M>(tesselation: &VoronoiTesselation<S, M>) -> CorridorGraph
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let vertices = vertices(tesselation);
+    let (nodes, cluster_of) = junctions(&vertices);
+
+    // Cells of the 2x2 blocks of every junction, where the borders end
+    let mut junction_cells: HashMap<GridIdx, usize> = HashMap::new();
+    for (vertex, &node) in vertices.iter().zip(&cluster_of) {
+        let (x, y) = vertex.position.coordinates();
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            junction_cells.insert(GridIdx::from((x + dx, y + dy)), node);
+        }
+    }
+
+    // Border cells between every pair of regions outside of the junctions,
+    // with the distance to their own site
+    let bounds = tesselation.bounds();
+    let mut borders: HashMap<(SiteOwner, SiteOwner), HashMap<GridIdx, f64>> = HashMap::new();
+    for (idx, cell) in tesselation.cells() {
+        let owner = match cell.owner() {
+            Some(owner) if !junction_cells.contains_key(&idx) => owner,
+            _ => continue
+        };
+        let site = tesselation.site(owner).expect("owners come from the tesselation");
+        let clearance: f64 = M::distance(site, &idx).into();
+
+        for neighbor in idx.neighbors(bounds) {
+            if let Some(other) = tesselation[neighbor].owner().filter(|&other| other != owner) {
+                borders.entry((owner.min(other), owner.max(other))).or_default().insert(idx, clearance);
+            }
+        }
+    }
+
+    let mut pairs: Vec<_> = borders.into_iter().collect();
+    pairs.sort_unstable_by_key(|&(pair, _)| pair);
+
+    let mut edges = Vec::new();
+    for ((first, second), mut border) in pairs {
+        let mut stretches = Vec::new();
+        while let Some(&start) = border.keys().min() {
+            // Walk the stretch of touching border cells, noting the junctions
+            // of both regions at either end
+            let mut cells = 0;
+            let mut clearance = border.remove(&start).expect("start is on the border");
+            let mut ends = BTreeSet::new();
+            let mut pending = vec![start];
+
+            while let Some(current) = pending.pop() {
+                cells += 1;
+                let (x, y) = current.coordinates();
+                for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                    let neighbor = GridIdx::from((x + dx, y + dy));
+                    if let Some(cell_clearance) = border.remove(&neighbor) {
+                        clearance = clearance.min(cell_clearance);
+                        pending.push(neighbor);
+                    } else if let Some(&node) = junction_cells.get(&neighbor) {
+                        if nodes[node].owners.contains(&first) && nodes[node].owners.contains(&second) {
+                            ends.insert(node);
+                        }
+                    }
+                }
+            }
+
+            stretches.push((ends, cells, clearance));
+        }
+
+        // Stretches come out in order of their first cell, so the edges do not
+        // depend on the iteration order of the map
+        for (ends, cells, clearance) in stretches {
+            let ends: Vec<usize> = ends.into_iter().collect();
+            for (position, &from) in ends.iter().enumerate() {
+                for &to in &ends[position + 1..] {
+                    edges.push(Corridor {
+                        from,
+                        to,
+                        between: (first, second),
+                        // Border cells are counted on both sides
+                        length: cells as f64 / 2.0,
+                        clearance
+                    });
+                }
+            }
+        }
+    }
+
+    CorridorGraph { nodes, edges }
+}
+
+// Connected piece of a region with a single other region all around it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enclave {
+    pub owner: SiteOwner,
+    pub surrounded_by: SiteOwner,
+    // Cells of the piece in row-major order
+    pub cells: Vec<GridIdx>,
+    // Whether the piece is cut off from the cell of its own site
+    pub exclave: bool
+}
+
+// Splits every region into pieces connected along cell edges and reports the
+// pieces whose neighbouring cells all belong to one other region, ordered by
+// their first cell. Pieces touching the edge of the bounds or an unowned
+// cell are never enclosed.
+pub fn enclaves<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<Enclave>
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let (width, height) = bounds.dimensions();
+    let mut visited = vec![false; width * height];
+    let mut found = Vec::new();
+
+    for (start, (idx, cell)) in tesselation.cells().iter().enumerate() {
+        let owner = match cell.owner() {
+            Some(owner) if !visited[start] => owner,
+            _ => continue
+        };
+
+        let mut cells = Vec::new();
+        let mut surrounding: Option<Option<SiteOwner>> = None;
+        let mut enclosed = true;
+        let mut pending = vec![idx];
+        visited[start] = true;
+
+        while let Some(idx) = pending.pop() {
+            cells.push(idx);
+            if idx.neighbors(bounds).count() < 4 {
+                enclosed = false;
+            }
+
+            for neighbor in idx.neighbors(bounds) {
+                let other = tesselation[neighbor].owner();
+                if other == Some(owner) {
+                    let (x, y) = bounds.translate_idx(neighbor);
+                    if !visited[x + y * width] {
+                        visited[x + y * width] = true;
+                        pending.push(neighbor);
+                    }
+                } else if other.is_none() || surrounding.is_some_and(|surrounding| surrounding != other) {
+                    enclosed = false;
+                } else {
+                    surrounding = Some(other);
+                }
+            }
+        }
+
+        let surrounded_by = match surrounding {
+            Some(Some(surrounded_by)) if enclosed => surrounded_by,
+            _ => continue
+        };
+
+        cells.sort_unstable_by_key(|idx| {
+            let (x, y) = idx.coordinates();
+            (y, x)
+        });
+        let site = tesselation.site(owner).expect("owners come from the tesselation");
+        let home = GridIdx::from(site.coordinates());
+        found.push(Enclave {
+            owner,
+            surrounded_by,
+            exclave: !cells.contains(&home),
+            cells
+        });
+    }
+
+    found
+}
+
+// Pairs of regions sharing an edge between their cells, the dual of the
+// tesselation, sorted with the lower id first
+pub fn adjacent_regions<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<(SiteOwner, SiteOwner)>
+where
+    S: Site,
+    M: Metric
+{
+    let bounds = tesselation.bounds();
+    let mut pairs = Vec::new();
+    for (idx, cell) in tesselation.cells() {
+        let owner = match cell.owner() {
+            Some(owner) => owner,
+            None => continue
+        };
+
+        for neighbor in idx.neighbors(bounds) {
+            match tesselation[neighbor].owner() {
+                Some(other) if other > owner => pairs.push((owner, other)),
+                _ => {}
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+// Adjacent regions whose sites have no other site inside of the circle with
+// the two sites as its diameter, measured between the site positions
+pub fn gabriel_graph<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<(SiteOwner, SiteOwner)>
+where
+    S: Site,
+    M: Metric
+{
+    proximity_graph(tesselation, |between, to_first, to_second| to_first + to_second < between)
+}
+
+// Adjacent regions whose sites have no other site closer to both of them
+// than they are to each other, measured between the site positions. Always
+// a subset of the Gabriel graph, which makes it a sparser road network.
+pub fn relative_neighborhood_graph<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<(SiteOwner, SiteOwner)>
+where
+    S: Site,
+    M: Metric
+{
+    proximity_graph(tesselation, |between, to_first, to_second| to_first.max(to_second) < between)
+}
+
+// Keeps the adjacent pairs without a site that blocks them, given the squared
+// distances between the pair and from the site to either of them. Every
+// pair is checked against every site.
+fn proximity_graph<S, M>(
+    tesselation: &VoronoiTesselation<S, M>,
+    blocks: impl Fn(f64, f64, f64) -> bool
+) -> Vec<(SiteOwner, SiteOwner)>
+where
+    S: Site,
+    M: Metric
+{
+    let positions: Vec<(f64, f64)> = tesselation
+        .owners()
+        .map(|owner| tesselation.site(owner).expect("owners come from the tesselation").position())
+        .collect();
+    let squared = |a: usize, b: usize| {
+        let ((ax, ay), (bx, by)) = (positions[a], positions[b]);
+        (ax - bx).powi(2) + (ay - by).powi(2)
+    };
+
+    adjacent_regions(tesselation)
+        .into_iter()
+        .filter(|&(first, second)| {
+            let (first, second) = (first.0 as usize, second.0 as usize);
+            let between = squared(first, second);
+            (0..positions.len())
+                .filter(|&other| other != first && other != second)
+                .all(|other| !blocks(between, squared(other, first), squared(other, second)))
+        })
+        .collect()
+}
+
+// Merges vertices whose blocks touch, including diagonally, along with the
+// junction of every vertex
+fn junctions(vertices: &[VoronoiVertex]) -> (Vec<CorridorNode>, Vec<usize>) {
+    let positions: HashMap<GridIdx, usize> =
+        vertices.iter().enumerate().map(|(idx, vertex)| (vertex.position, idx)).collect();
+    let mut cluster_of: Vec<Option<usize>> = vec![None; vertices.len()];
+    let mut nodes: Vec<CorridorNode> = Vec::new();
+
+    for start in 0..vertices.len() {
+        if cluster_of[start].is_some() {
+            continue;
+        }
+
+        let cluster = nodes.len();
+        let mut owners = Vec::new();
+        let mut pending = vec![start];
+        cluster_of[start] = Some(cluster);
+        while let Some(current) = pending.pop() {
+            owners.extend_from_slice(&vertices[current].owners);
+
+            // Blocks overlap up to one cell apart and touch up to two apart
+            let (x, y) = vertices[current].position.coordinates();
+            for (dx, dy) in (-2..=2).flat_map(|dx| (-2..=2).map(move |dy| (dx, dy))) {
+                let neighbor = GridIdx::from((x + dx, y + dy));
+                if let Some(&next) = positions.get(&neighbor) {
+                    if cluster_of[next].is_none() {
+                        cluster_of[next] = Some(cluster);
+                        pending.push(next);
+                    }
+                }
+            }
+        }
+
+        owners.sort_unstable();
+        owners.dedup();
+        nodes.push(CorridorNode {
+            position: vertices[start].position,
+            owners
+        });
+    }
+
+    let cluster_of = cluster_of.into_iter().map(|cluster| cluster.expect("every vertex is clustered")).collect();
+    (nodes, cluster_of)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::grid::BoundingBox;
+    use crate::metric::MultWeightedEuclidean;
+
+    #[test]
+    fn three_regions_meet_once() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (7, 0, 1f32), (3, 7, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 8, 8)).build().unwrap();
+        tess.compute();
+
+        let found = vertices(&tess);
+
+        assert!(!found.is_empty());
+        for vertex in found {
+            assert_eq!(vertex.owners, vec![SiteOwner(0), SiteOwner(1), SiteOwner(2)]);
+        }
+    }
+
+    #[test]
+    fn two_regions_have_no_vertices() {
+        let sites: Vec<(isize, isize, f32)> = vec![(0, 0, 1f32), (7, 7, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).build().unwrap();
+        tess.compute();
+
+        assert!(vertices(&tess).is_empty());
+    }
+
+    #[test]
+    fn corridors_between_obstacles() {
+        // Four obstacles around a central block leave a cross of corridors
+        let sites: Vec<(isize, isize, f32)> =
+            vec![(2, 2, 1f32), (10, 2, 1f32), (6, 6, 1f32), (2, 10, 1f32), (10, 10, 1f32)];
+
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 13, 13)).build().unwrap();
+        tess.compute();
+
+        let graph = corridor_graph(&tess);
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 4);
+        for edge in &graph.edges {
+            assert!(edge.between.0 == SiteOwner(2) || edge.between.1 == SiteOwner(2));
+            assert!(edge.length > 0.0 && edge.clearance > 0.0);
+        }
+    }
+
+    #[test]
+    fn corridors_keep_their_own_width() {
+        // Light obstacles along the middle only win small discs
+        let sites = vec![(4, 2, 1f32), (4, 14, 1f32), (0, 8, 0.2f32), (9, 8, 0.2f32), (20, 8, 0.2f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<MultWeightedEuclidean>()
+            .bounds(BoundingBox::new(0, 0, 21, 17))
+            .build()
+            .unwrap();
+        tess.compute_exact();
+
+        // The obstacles split the border between the two big regions into a
+        // narrow stretch on the left and a wide one on the right
+        let graph = corridor_graph(&tess);
+        let between: Vec<&Corridor> =
+            graph.edges.iter().filter(|edge| edge.between == (SiteOwner(1), SiteOwner(2))).collect();
+        assert_eq!(between.len(), 2);
+        assert_eq!((between[0].from, between[0].to), (0, 1));
+        assert_eq!((between[1].from, between[1].to), (2, 3));
+        assert_eq!(between[0].clearance, 5.0);
+        assert!(between[1].clearance > 9.0);
+        assert!(between[0].length < between[1].length);
+    }
+
+    #[test]
+    fn proximity_graphs_between_sites() {
+        let sites = vec![(0, 0, 1f32), (1, 8, 1f32), (4, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 5, 9)).build().unwrap();
+        tess.compute();
+
+        let all = vec![(SiteOwner(0), SiteOwner(1)), (SiteOwner(0), SiteOwner(2)), (SiteOwner(1), SiteOwner(2))];
+        assert_eq!(adjacent_regions(&tess), all);
+        assert_eq!(gabriel_graph(&tess), all);
+        // The first site is closer to both ends of the long edge
+        assert_eq!(relative_neighborhood_graph(&tess), all[..2].to_vec());
+    }
+
+    #[test]
+    fn find_enclosed_regions() {
+        // The light site only wins a disc around itself
+        let sites = vec![(2, 5, 1f32), (6, 5, 0.5f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<MultWeightedEuclidean>()
+            .bounds(BoundingBox::new(0, 0, 13, 11))
+            .build()
+            .unwrap();
+        tess.compute_exact();
+
+        let found = enclaves(&tess);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].owner, SiteOwner(1));
+        assert_eq!(found[0].surrounded_by, SiteOwner(0));
+        assert!(!found[0].exclave);
+        assert_eq!(found[0].cells.len(), tess.region_of(SiteOwner(1)).count());
+
+        let sites: Vec<(isize, isize, f32)> = vec![(2, 5, 1f32), (6, 5, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites).bounds(BoundingBox::new(0, 0, 13, 11)).build().unwrap();
+        tess.compute();
+        assert!(enclaves(&tess).is_empty());
+    }
+}
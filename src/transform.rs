@@ -0,0 +1,146 @@
+use crate::discrete_voronoi::VoronoiTesselation;
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::propagate::propagate;
+use crate::site::{Point, Site};
+
+// Distance from every cell of the bounds to the closest seed under the
+// metric, in row-major order. The field spreads from the seeds one cell at a
+// time with the closest cells settled first, so it is only exact for metrics
+// where growing regions is, see distance_transform_exact() for the others.
+// Seeds outside of the bounds are ignored, failing with NoSitesInBounds if
+// that leaves none.
+pub fn distance_transform<M>(seeds: &[(isize, isize)], bounds: BoundingBox) -> Result<Vec<f64>, VoronoiError>
+where
+    M: Metric,
+    M::Output: Into<f64>
+{
+    check_seeds(seeds, &bounds)?;
+
+    let distance = |source: usize, idx: GridIdx| M::distance(&seeds[source], &idx).into();
+    let starts = seeds.iter().enumerate().map(|(source, &seed)| {
+        let idx = GridIdx::from(seed);
+        (idx, source, distance(source, idx))
+    });
+    let propagation = propagate(&bounds, starts, |source, _, _, neighbor| Some(distance(source, neighbor)));
+
+    Ok(propagation.priority)
+}
+
+// Like distance_transform(), but measures every cell against every seed
+pub fn distance_transform_exact<M>(seeds: &[(isize, isize)], bounds: BoundingBox) -> Result<Vec<f64>, VoronoiError>
+where
+    M: Metric,
+    M::Output: Into<f64>
+{
+    check_seeds(seeds, &bounds)?;
+
+    let inside: Vec<&(isize, isize)> = seeds.iter().filter(|&&seed| GridIdx::from(seed).inside(&bounds)).collect();
+    let field = bounds
+        .coordinates_iter()
+        .map(|idx| inside.iter().map(|&seed| M::distance(seed, &idx).into()).fold(f64::INFINITY, f64::min))
+        .collect();
+
+    Ok(field)
+}
+
+// Distance from every cell to the closest cell on a region border, in
+// row-major order under the metric of the tesselation. Border cells touch a
+// cell with another owner along an edge and are at distance 0, every cell is
+// infinitely far away if there are no borders.
+pub fn border_distances<S, M>(tesselation: &VoronoiTesselation<S, M>) -> Vec<f64>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    let bounds = *tesselation.bounds();
+    let borders: Vec<(isize, isize)> = tesselation
+        .cells()
+        .iter()
+        .filter(|(idx, cell)| idx.neighbors(&bounds).any(|neighbor| tesselation[neighbor].owner() != cell.owner()))
+        .map(|(idx, _)| idx.coordinates())
+        .collect();
+
+    if borders.is_empty() {
+        return vec![f64::INFINITY; tesselation.cells().len()];
+    }
+
+    distance_transform::<M>(&borders, bounds).expect("the bounds of a tesselation are valid")
+}
+
+// Cells closer to a region border than the thickness, in row-major order,
+// for drawing thick borders or keeping buffer zones between regions. A
+// thickness of 1 only marks the border cells on both sides.
+pub fn border_band<S, M>(tesselation: &VoronoiTesselation<S, M>, thickness: f64) -> Vec<bool>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    border_distances(tesselation).into_iter().map(|distance| distance < thickness).collect()
+}
+
+fn check_seeds(seeds: &[(isize, isize)], bounds: &BoundingBox) -> Result<(), VoronoiError> {
+    bounds.validate()?;
+    if seeds.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+    if !seeds.iter().any(|&seed| GridIdx::from(seed).inside(bounds)) {
+        return Err(VoronoiError::NoSitesInBounds);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discrete_voronoi::VoronoiBuilder;
+    use crate::metric::{Chebyshev, Euclidean, Manhattan};
+
+    #[test]
+    fn transform_distances() {
+        let bounds = BoundingBox::new(0, 0, 4, 3);
+        let field = distance_transform::<Manhattan>(&[(0, 0), (3, 2)], bounds).unwrap();
+
+        assert_eq!(field, vec![0.0, 1.0, 2.0, 2.0, 1.0, 2.0, 2.0, 1.0, 2.0, 2.0, 1.0, 0.0]);
+        assert_eq!(distance_transform_exact::<Manhattan>(&[(0, 0), (3, 2)], bounds).unwrap(), field);
+
+        let field = distance_transform::<Chebyshev>(&[(1, 1)], bounds).unwrap();
+        assert_eq!(field, vec![1.0, 1.0, 1.0, 2.0, 1.0, 0.0, 1.0, 2.0, 1.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn transform_ignores_seeds_outside() {
+        let bounds = BoundingBox::new(0, 0, 9, 7);
+        let seeds = [(1, 1), (7, 2), (3, 5), (20, 3), (4, 4)];
+        let field = distance_transform::<Euclidean>(&seeds, bounds).unwrap();
+        assert_eq!(field, distance_transform_exact::<Euclidean>(&seeds, bounds).unwrap());
+        assert_eq!(field[1 + 9], 0.0);
+        assert_eq!(field[8 + 2 * 9], 1.0);
+
+        assert_eq!(distance_transform::<Euclidean>(&[(20, 3)], bounds), Err(VoronoiError::NoSitesInBounds));
+        assert_eq!(distance_transform_exact::<Euclidean>(&[], bounds), Err(VoronoiError::NoSites));
+    }
+
+    #[test]
+    fn bands_along_borders() {
+        let sites = vec![(0, 0, 1f32), (7, 0, 1f32)];
+        let mut tess = VoronoiBuilder::new(sites)
+            .metric::<Manhattan>()
+            .bounds(BoundingBox::new(0, 0, 8, 2))
+            .build()
+            .unwrap();
+        tess.compute();
+
+        let distances = border_distances(&tess);
+        assert_eq!(&distances[..8], &[3.0, 2.0, 1.0, 0.0, 0.0, 1.0, 2.0, 3.0]);
+
+        let band = border_band(&tess, 2.0);
+        assert_eq!(band.iter().filter(|&&inside| inside).count(), 8);
+        assert!(band[2] && band[5] && !band[1] && !band[6]);
+        assert!(border_band(&tess, 0.0).iter().all(|&inside| !inside));
+    }
+}
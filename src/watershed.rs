@@ -0,0 +1,119 @@
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::propagate::propagate;
+use crate::site::Point;
+
+use image::GrayImage;
+
+// Marker of every pixel in row-major order, as positions in the markers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watershed {
+    pub width: u32,
+    pub height: u32,
+    pub labels: Vec<Option<usize>>
+}
+
+impl Watershed {
+    pub fn label_at(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            self.labels[(x + y * self.width) as usize]
+        } else {
+            None
+        }
+    }
+}
+
+// Sobel gradient magnitude of every pixel in row-major order, edges of the
+// image repeat the outermost pixels
+pub fn gradient_magnitude(image: &GrayImage) -> Vec<f64> {
+    let (width, height) = image.dimensions();
+    let value = |x: i64, y: i64| {
+        let x = x.clamp(0, width as i64 - 1) as u32;
+        let y = y.clamp(0, height as i64 - 1) as u32;
+        f64::from(image.get_pixel(x, y).0[0])
+    };
+
+    let mut gradient = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            let dx = value(x + 1, y - 1) + 2.0 * value(x + 1, y) + value(x + 1, y + 1)
+                - value(x - 1, y - 1)
+                - 2.0 * value(x - 1, y)
+                - value(x - 1, y + 1);
+            let dy = value(x - 1, y + 1) + 2.0 * value(x, y + 1) + value(x + 1, y + 1)
+                - value(x - 1, y - 1)
+                - 2.0 * value(x, y - 1)
+                - value(x + 1, y - 1);
+            gradient.push((dx * dx + dy * dy).sqrt());
+        }
+    }
+
+    gradient
+}
+
+// Marker based watershed: the basin of every marker floods the gradient
+// raster, given in row-major order, from the lowest level up and the basins
+// meet along the ridges of the gradient. Every pixel is labelled, ties go
+// to the earlier marker and markers outside of the image are ignored.
+pub fn watershed<P>(gradient: &[f64], width: u32, height: u32, markers: &[P]) -> Result<Watershed, VoronoiError>
+where
+    P: Point
+{
+    let bounds = BoundingBox::new(0, 0, width as usize, height as usize);
+    bounds.validate()?;
+    if gradient.len() != width as usize * height as usize {
+        return Err(VoronoiError::BufferLength {
+            expected: width as usize * height as usize,
+            actual: gradient.len()
+        });
+    }
+    if markers.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+
+    let level = |idx: GridIdx| {
+        let position = bounds.linear_idx(idx).expect("flooded pixels are inside of the image");
+        gradient[position]
+    };
+    let seeds = markers.iter().enumerate().map(|(marker, point)| {
+        let idx = GridIdx::from(point.coordinates());
+        (idx, marker, f64::NEG_INFINITY)
+    });
+
+    let propagation = propagate(&bounds, seeds, |_, _, flooded, neighbor| Some(flooded.max(level(neighbor))));
+
+    Ok(Watershed {
+        width,
+        height,
+        labels: propagation.source
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    #[test]
+    fn split_along_edge() {
+        // A dark left part and a bright right part
+        let image = GrayImage::from_fn(9, 4, |x, _| if x < 4 { Luma([20]) } else { Luma([220]) });
+        let gradient = gradient_magnitude(&image);
+        assert_eq!(gradient[0], 0.0);
+        assert!(gradient[3] > 0.0);
+
+        // The gradient is as high on both sides of the edge, so the first
+        // marker takes the ridge
+        let basins = watershed(&gradient, 9, 4, &[(0, 1), (8, 2)]).unwrap();
+        for y in 0..4 {
+            for x in 0..9 {
+                assert_eq!(basins.label_at(x, y), Some(if x < 5 { 0 } else { 1 }));
+            }
+        }
+        assert_eq!(basins.label_at(9, 0), None);
+
+        let short = watershed(&gradient[1..], 9, 4, &[(0, 1)]);
+        assert_eq!(short, Err(VoronoiError::BufferLength { expected: 36, actual: 35 }));
+        assert_eq!(watershed::<(isize, isize)>(&gradient, 9, 4, &[]), Err(VoronoiError::NoSites));
+    }
+}
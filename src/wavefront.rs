@@ -0,0 +1,303 @@
+use crate::error::VoronoiError;
+use crate::grid::{BoundingBox, GridIdx};
+use crate::metric::Metric;
+use crate::propagate::propagate;
+use crate::site::Site;
+
+// When a site starts spreading and how many units of distance it covers per
+// unit of time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Launch {
+    pub start: f64,
+    pub speed: f64
+}
+
+impl Default for Launch {
+    fn default() -> Self {
+        Launch {
+            start: 0.0,
+            speed: 1.0
+        }
+    }
+}
+
+// Arrival time and source of the first wave to reach every cell, in
+// row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wavefront {
+    bounds: BoundingBox,
+    arrivals: Vec<f64>,
+    sources: Vec<Option<usize>>
+}
+
+impl Wavefront {
+    pub fn bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    // Infinite for cells no wave reached
+    pub fn arrivals(&self) -> &[f64] {
+        &self.arrivals
+    }
+
+    // Positions of the sites in the input
+    pub fn sources(&self) -> &[Option<usize>] {
+        &self.sources
+    }
+
+    // None outside of the bounds and for cells no wave reached
+    pub fn arrival_at(&self, coordinates: (isize, isize)) -> Option<f64> {
+        let position = self.bounds.linear_idx(GridIdx::from(coordinates))?;
+        Some(self.arrivals[position]).filter(|arrival| arrival.is_finite())
+    }
+
+    pub fn source_at(&self, coordinates: (isize, isize)) -> Option<usize> {
+        let position = self.bounds.linear_idx(GridIdx::from(coordinates))?;
+        self.sources[position]
+    }
+}
+
+// Spreads a wave from every site, starting at its launch time and reaching a
+// cell once the distance under the metric divided by its speed has passed.
+// Waves only travel through cells they reached first, like a fire that
+// cannot burn through ground another fire already burned, and cells reached
+// at the same time go to the earlier site. Sites outside of the bounds never
+// start spreading.
+pub fn wavefront<S, M>(sites: &[S], launches: &[Launch], bounds: BoundingBox) -> Result<Wavefront, VoronoiError>
+where
+    S: Site,
+    M: Metric,
+    M::Output: Into<f64>
+{
+    bounds.validate()?;
+    if sites.is_empty() {
+        return Err(VoronoiError::NoSites);
+    }
+    if launches.len() != sites.len() {
+        return Err(VoronoiError::BufferLength {
+            expected: sites.len(),
+            actual: launches.len()
+        });
+    }
+    for (index, launch) in launches.iter().enumerate() {
+        if !launch.start.is_finite() || !launch.speed.is_finite() || launch.speed <= 0.0 {
+            return Err(VoronoiError::InvalidWeight(index));
+        }
+    }
+
+    let arrival = |source: usize, idx: GridIdx| {
+        let launch = &launches[source];
+        launch.start + M::distance(&sites[source], &idx).into() / launch.speed
+    };
+    let seeds = sites.iter().enumerate().map(|(source, site)| {
+        let idx = GridIdx::from(site.coordinates());
+        (idx, source, arrival(source, idx))
+    });
+
+    let propagation = propagate(&bounds, seeds, |source, _, _, neighbor| Some(arrival(source, neighbor)));
+
+    Ok(Wavefront {
+        bounds,
+        arrivals: propagation.priority,
+        sources: propagation.source
+    })
+}
+
+// Grain growth played back in fixed time steps from time 0, every site
+// growing at its own speed. A cell is claimed once the time passed times the
+// speed of a site reaches its distance, unless another grain got there first,
+// which gives diagrams like the multiplicatively weighted ones with grains
+// that stay connected.
+#[derive(Debug, Clone)]
+pub struct CrystalGrowth {
+    wavefront: Wavefront,
+    // Positions of the reached cells sorted by arrival
+    order: Vec<usize>,
+    claimed: usize,
+    time: f64,
+    time_step: f64
+}
+
+impl CrystalGrowth {
+    pub fn new<S, M>(sites: &[S], speeds: &[f64], bounds: BoundingBox, time_step: f64) -> Result<Self, VoronoiError>
+    where
+        S: Site,
+        M: Metric,
+        M::Output: Into<f64>
+    {
+        let launches: Vec<Launch> = speeds.iter().map(|&speed| Launch { start: 0.0, speed }).collect();
+
+        Self::with_launches::<S, M>(sites, &launches, bounds, time_step)
+    }
+
+    // Like new(), but every site only starts growing at its launch time, so
+    // sites founded later have to make do with the space the earlier ones
+    // left them. With equal speeds this matches additively weighted regions.
+    pub fn with_launches<S, M>(
+        sites: &[S],
+        launches: &[Launch],
+        bounds: BoundingBox,
+        time_step: f64
+    ) -> Result<Self, VoronoiError>
+    where
+        S: Site,
+        M: Metric,
+        M::Output: Into<f64>
+    {
+        if !time_step.is_finite() || time_step <= 0.0 {
+            return Err(VoronoiError::InvalidTimeStep);
+        }
+
+        let wavefront = wavefront::<S, M>(sites, launches, bounds)?;
+
+        let mut order: Vec<usize> = (0..wavefront.arrivals.len())
+            .filter(|&position| wavefront.sources[position].is_some())
+            .collect();
+        order.sort_by(|&a, &b| wavefront.arrivals[a].total_cmp(&wavefront.arrivals[b]).then(a.cmp(&b)));
+
+        Ok(CrystalGrowth {
+            wavefront,
+            order,
+            claimed: 0,
+            time: 0.0,
+            time_step
+        })
+    }
+
+    // Time at the end of the last step
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    // True once every cell any grain can reach has been claimed
+    pub fn is_complete(&self) -> bool {
+        self.claimed == self.order.len()
+    }
+
+    // Advances the time by one step and returns the cells claimed during it
+    // with the positions of their sites, in order of arrival. Sites claim
+    // their own cell in the step their launch time falls in.
+    pub fn step(&mut self) -> Vec<(GridIdx, usize)> {
+        self.time += self.time_step;
+
+        let (width, _) = self.wavefront.bounds.dimensions();
+        let (x_offset, y_offset) = self.wavefront.bounds.offset();
+        let mut claims = Vec::new();
+        while let Some(&position) = self.order.get(self.claimed) {
+            if self.wavefront.arrivals[position] > self.time {
+                break;
+            }
+
+            let idx = GridIdx::from((x_offset + (position % width) as isize, y_offset + (position / width) as isize));
+            let source = self.wavefront.sources[position].expect("only reached cells are ordered");
+            claims.push((idx, source));
+            self.claimed += 1;
+        }
+
+        claims
+    }
+
+    // Site of every cell at the current time in row-major order
+    pub fn owners(&self) -> Vec<Option<usize>> {
+        let mut owners = vec![None; self.wavefront.sources.len()];
+        for &position in &self.order[..self.claimed] {
+            owners[position] = self.wavefront.sources[position];
+        }
+
+        owners
+    }
+
+    // Arrival times and sites of the finished growth
+    pub fn finish(self) -> Wavefront {
+        self.wavefront
+    }
+}
+
+// Yields the claims of every step until the growth is complete
+impl Iterator for CrystalGrowth {
+    type Item = Vec<(GridIdx, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_complete() {
+            None
+        } else {
+            Some(self.step())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Manhattan;
+    use crate::site::Point;
+
+    #[test]
+    fn faster_wave_claims_more() {
+        let bounds = BoundingBox::new(0, 0, 10, 1);
+        let sites = [(0, 0, 1f32), (9, 0, 1f32)];
+        let launches = [Launch::default(), Launch { start: 0.0, speed: 2.0 }];
+
+        let wave = wavefront::<_, Manhattan>(&sites, &launches, bounds).unwrap();
+        let sources: Vec<Option<usize>> = (0..10).map(|x| wave.source_at((x, 0))).collect();
+        assert_eq!(sources, [0, 0, 0, 0, 1, 1, 1, 1, 1, 1].map(Some));
+        assert_eq!(wave.arrival_at((3, 0)), Some(3.0));
+        assert_eq!(wave.arrival_at((4, 0)), Some(2.5));
+        assert_eq!(wave.arrival_at((10, 0)), None);
+
+        // A late site is overrun before it starts
+        let launches = [Launch::default(), Launch { start: 10.0, speed: 2.0 }];
+        let wave = wavefront::<_, Manhattan>(&sites, &launches, bounds).unwrap();
+        assert!(wave.sources().iter().all(|&source| source == Some(0)));
+        assert_eq!(wave.arrival_at((9, 0)), Some(9.0));
+
+        assert_eq!(
+            wavefront::<_, Manhattan>(&sites, &launches[..1], bounds),
+            Err(VoronoiError::BufferLength { expected: 2, actual: 1 })
+        );
+        let stopped = [Launch::default(), Launch { start: 0.0, speed: 0.0 }];
+        assert_eq!(wavefront::<_, Manhattan>(&sites, &stopped, bounds), Err(VoronoiError::InvalidWeight(1)));
+    }
+
+    #[test]
+    fn grow_crystals_in_steps() {
+        let bounds = BoundingBox::new(0, 0, 10, 1);
+        let sites = [(0, 0, 1f32), (9, 0, 1f32)];
+
+        let mut growth = CrystalGrowth::new::<_, Manhattan>(&sites, &[1.0, 2.0], bounds, 1.0).unwrap();
+        let claims: Vec<((isize, isize), usize)> =
+            growth.step().into_iter().map(|(idx, site)| (idx.coordinates(), site)).collect();
+        assert_eq!(claims, vec![((0, 0), 0), ((9, 0), 1), ((8, 0), 1), ((1, 0), 0), ((7, 0), 1)]);
+        assert_eq!(growth.time(), 1.0);
+        assert_eq!(growth.owners().iter().filter(|owner| owner.is_some()).count(), 5);
+
+        let steps: Vec<Vec<(GridIdx, usize)>> = growth.by_ref().collect();
+        assert_eq!(steps.len(), 2);
+        assert!(growth.is_complete());
+        assert_eq!(growth.owners(), [0, 0, 0, 0, 1, 1, 1, 1, 1, 1].map(Some));
+        assert_eq!(growth.finish().arrival_at((4, 0)), Some(2.5));
+
+        assert_eq!(
+            CrystalGrowth::new::<_, Manhattan>(&sites, &[1.0, 2.0], bounds, 0.0).err(),
+            Some(VoronoiError::InvalidTimeStep)
+        );
+    }
+
+    #[test]
+    fn delay_late_sites() {
+        let bounds = BoundingBox::new(0, 0, 10, 1);
+        let sites = [(0, 0, 1f32), (9, 0, 1f32)];
+        let launches = [Launch::default(), Launch { start: 4.0, speed: 1.0 }];
+
+        let mut growth = CrystalGrowth::with_launches::<_, Manhattan>(&sites, &launches, bounds, 2.0).unwrap();
+        growth.step();
+        // The late site has not started yet
+        assert_eq!(growth.owners()[9], None);
+        assert_eq!(growth.owners()[2], Some(0));
+        growth.step();
+        assert_eq!(growth.owners()[9], Some(1));
+
+        growth.by_ref().for_each(drop);
+        assert_eq!(growth.owners(), [0, 0, 0, 0, 0, 0, 0, 1, 1, 1].map(Some));
+    }
+}